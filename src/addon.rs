@@ -1,4 +1,12 @@
+pub mod json_style;
+pub mod known_packs;
+pub mod lang;
+pub mod lint;
 pub mod manifest;
+pub mod scaffold;
+pub mod scanner;
+pub mod selector;
+pub mod trash;
 pub mod world_packet_list;
 
 use manifest::version::AddonVersion;
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// How an existing json file is written, so that rewriting it keeps its indentation and the
+/// order of its keys instead of reformatting everything. Comments are not kept, a file read
+/// with comments is written without them.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct JsonStyle {
+    /// `None` writes everything on a single line.
+    indent: Option<String>,
+    trailing_newline: bool,
+    /// The original document, the keys of the rewritten objects follow its order.
+    template: Option<Value>,
+}
+
+impl JsonStyle {
+    /// Everything on a single line, as the pack lists are written by default.
+    pub fn compact() -> JsonStyle {
+        JsonStyle::default()
+    }
+
+    /// Two spaces per level, for the files this tool creates.
+    pub fn pretty() -> JsonStyle {
+        JsonStyle {
+            indent: Some("  ".to_string()),
+            trailing_newline: true,
+            template: None,
+        }
+    }
+
+    /// Learn the style of an existing file, which may contain comments that are then lost.
+    pub fn of<S: AsRef<str>>(str: S) -> JsonStyle {
+        let str = str.as_ref();
+        let indent = if str.trim().contains('\n') {
+            let indent = str
+                .lines()
+                .skip(1)
+                .filter(|i| !i.trim().is_empty())
+                .map(|i| &i[..i.len() - i.trim_start().len()])
+                .find(|i| !i.is_empty())
+                .unwrap_or("  ");
+            Some(indent.to_string())
+        } else {
+            None
+        };
+        JsonStyle {
+            indent,
+            trailing_newline: str.ends_with('\n'),
+            template: serde_jsonc::from_str(str).ok(),
+        }
+    }
+
+    pub fn to_string<T: Serialize>(&self, value: &T) -> serde_json::Result<String> {
+        let mut value = serde_json::to_value(value)?;
+        if let Some(template) = &self.template {
+            value = reorder(value, template);
+        }
+        let mut out = match &self.indent {
+            Some(indent) => {
+                let mut out = String::new();
+                write_pretty(&mut out, &value, indent, 0)?;
+                out
+            }
+            None => serde_json::to_string(&value)?,
+        };
+        if self.trailing_newline {
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Write one value per line, except for arrays of numbers such as versions, which stay on a
+/// single line as they are usually written by hand.
+fn write_pretty(
+    out: &mut String,
+    value: &Value,
+    indent: &str,
+    level: usize,
+) -> serde_json::Result<()> {
+    let newline = |out: &mut String, level: usize| {
+        out.push('\n');
+        out.push_str(&indent.repeat(level));
+    };
+    match value {
+        Value::Array(items) if items.iter().all(Value::is_number) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                out.push_str(&serde_json::to_string(item)?);
+            }
+            out.push(']');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                newline(out, level + 1);
+                write_pretty(out, item, indent, level + 1)?;
+            }
+            newline(out, level);
+            out.push(']');
+        }
+        Value::Object(map) if !map.is_empty() => {
+            out.push('{');
+            for (index, (key, item)) in map.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                newline(out, level + 1);
+                out.push_str(&serde_json::to_string(key)?);
+                out.push_str(": ");
+                write_pretty(out, item, indent, level + 1)?;
+            }
+            newline(out, level);
+            out.push('}');
+        }
+        value => out.push_str(&serde_json::to_string(value)?),
+    }
+    Ok(())
+}
+
+/// Put the keys that are in the template in its order, followed by the new keys.
+/// The elements of an array follow the element at the same position, or the first one.
+fn reorder(value: Value, template: &Value) -> Value {
+    match (value, template) {
+        (Value::Object(mut map), Value::Object(template)) => {
+            let mut out = Map::new();
+            for (key, template) in template {
+                if let Some(value) = map.shift_remove(key) {
+                    out.insert(key.clone(), reorder(value, template));
+                }
+            }
+            out.extend(map);
+            Value::Object(out)
+        }
+        (Value::Array(items), Value::Array(template)) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(
+                    |(index, value)| match template.get(index).or(template.first()) {
+                        Some(template) => reorder(value, template),
+                        None => value,
+                    },
+                )
+                .collect(),
+        ),
+        (value, _) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reorder_follows_template() {
+        let template = json!({"b": 1, "a": {"y": 1, "x": 1}, "list": [{"k": 1, "j": 1}]});
+        let value = json!({
+            "a": {"x": 2, "y": 2, "z": 2},
+            "new": 2,
+            "b": 2,
+            "list": [{"j": 2, "k": 2}, {"j": 3, "k": 3}]
+        });
+        assert_eq!(
+            serde_json::to_string(&reorder(value, &template)).unwrap(),
+            r#"{"b":2,"a":{"y":2,"x":2,"z":2},"list":[{"k":2,"j":2},{"k":3,"j":3}],"new":2}"#
+        );
+    }
+
+    #[test]
+    fn keeps_style() {
+        let text = "{\n    \"version\": [1, 0, 0],\n    \"name\": \"x\"\n}\n";
+        let value: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(JsonStyle::of(text).to_string(&value).unwrap(), text);
+        let compact = r#"{"b":[1],"a":"x"}"#;
+        let value: Value = serde_json::from_str(compact).unwrap();
+        assert_eq!(JsonStyle::of(compact).to_string(&value).unwrap(), compact);
+    }
+
+    #[test]
+    fn stored_style() {
+        let text = "[\n    {\n        \"pack_id\": \"a\",\n        \"subpack\": \"s\",\n        \"version\": [1, 0, 0]\n    }\n]\n";
+        let style: JsonStyle =
+            serde_json::from_str(&serde_json::to_string(&JsonStyle::of(text)).unwrap()).unwrap();
+        let value = json!([{"pack_id": "a", "version": [1, 0, 0], "subpack": "s"}]);
+        assert_eq!(style.to_string(&value).unwrap(), text);
+    }
+}
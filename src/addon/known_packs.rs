@@ -0,0 +1,36 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{fs, path::Path};
+
+/// The list of packs the server has validated, in the root of the bds.
+pub const KNOWN_PACKS_FILE: &str = "valid_known_packs.json";
+
+/// An entry of `valid_known_packs.json`. The first entry of the file only holds the `file_version`,
+/// so every field is optional.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KnownPack {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_system: Option<String>,
+    /// Path of the pack folder relative to the bds root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+pub fn parse_known_packs<S: AsRef<str>>(str: S) -> Result<Vec<KnownPack>, serde_json::Error> {
+    serde_json::from_str(str.as_ref())
+}
+
+pub fn read_known_packs<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<KnownPack>> {
+    let path = path.as_ref();
+    parse_known_packs(
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?,
+    )
+    .with_context(|| format!("Failed to parse {}", path.display()))
+}
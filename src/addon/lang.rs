@@ -0,0 +1,78 @@
+use std::{collections::HashMap, fs, path::Path};
+
+pub const DEFAULT_LANG: &str = "en_US";
+
+/// The strings of a `texts/<lang>.lang` file.
+#[derive(Default, Debug, Clone)]
+pub struct Lang {
+    entries: HashMap<String, String>,
+}
+
+impl Lang {
+    pub fn parse<S: AsRef<str>>(str: S) -> Lang {
+        let entries = str
+            .as_ref()
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .filter(|i| !i.trim_start().starts_with("##"))
+            .filter_map(|i| i.split_once('='))
+            .map(|(key, value)| {
+                // everything after `##` is a comment, it is usually separated by a tab
+                let value = value.split_once("##").map(|i| i.0).unwrap_or(value);
+                (key.trim().to_string(), value.trim_end().to_string())
+            })
+            .collect();
+        Lang { entries }
+    }
+
+    /// Load the strings of a pack, falling back to en_US and then to the first language of
+    /// `texts/languages.json` when the pack is not translated to `lang`.
+    pub fn load<P: AsRef<Path>>(pack: P, lang: &str) -> Option<Lang> {
+        let texts = pack.as_ref().join("texts");
+        let mut candidates = vec![lang.to_string(), DEFAULT_LANG.to_string()];
+        if let Ok(str) = fs::read_to_string(texts.join("languages.json")) {
+            if let Ok(languages) = serde_jsonc::from_str::<Vec<String>>(&str) {
+                candidates.extend(languages.into_iter().take(1));
+            }
+        }
+        let files = texts
+            .read_dir()
+            .ok()?
+            .flatten()
+            .map(|i| i.path())
+            .collect::<Vec<_>>();
+        candidates.iter().find_map(|lang| {
+            files
+                .iter()
+                .find(|i| {
+                    i.file_name()
+                        .is_some_and(|i| i.eq_ignore_ascii_case(format!("{lang}.lang").as_str()))
+                })
+                .and_then(|i| fs::read_to_string(i).ok())
+                .map(Lang::parse)
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Look the text up as a key, returning the text itself when it is not one.
+    pub fn resolve<'a>(&'a self, text: &'a str) -> &'a str {
+        self.get(text).unwrap_or(text)
+    }
+}
+
+/// Remove the `§` formatting codes, such as colors and bold, from a text.
+pub fn strip_formatting<S: AsRef<str>>(text: S) -> String {
+    let mut out = String::new();
+    let mut chars = text.as_ref().chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,605 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::manifest::{
+    format::{self, FormatIssue},
+    Manifest, ModuleType, PackateType,
+};
+
+/// What a finding of a rule does: nothing, a warning, or an error that fails the lint.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Allow => "allow",
+            Severity::Warn => "warn",
+            Severity::Deny => "deny",
+        })
+    }
+}
+
+pub struct Rule {
+    /// Stable id used to configure the rule and shown with its findings.
+    pub id: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+}
+
+macro_rules! rules {
+    ($($name:ident = $id:literal, $severity:ident, $description:literal;)*) => {
+        $(pub const $name: &str = $id;)*
+        pub const RULES: &[Rule] = &[$(Rule {
+            id: $id,
+            severity: Severity::$severity,
+            description: $description,
+        }),*];
+    };
+}
+
+rules! {
+    INVALID_MANIFEST = "invalid-manifest", Deny, "the manifest can not be parsed";
+    INVALID_UUID = "invalid-uuid", Deny, "a uuid of the header, a module or a dependency is not a valid uuid";
+    REUSED_UUID = "reused-uuid", Deny, "the same uuid is used by the header and a module or by two packs";
+    EMPTY_NAME = "empty-name", Deny, "the header has an empty name";
+    MISSING_DESCRIPTION = "missing-description", Warn, "the header has no description";
+    NO_MODULES = "no-modules", Deny, "the manifest has no module";
+    MISSING_MODULE_UUID = "missing-module-uuid", Deny, "a module has no uuid";
+    MISSING_MODULE_VERSION = "missing-module-version", Deny, "a module has no version";
+    MIXED_MODULE_TYPES = "mixed-module-types", Deny, "the pack has both resource and behavior modules";
+    NOT_A_PACK = "not-a-pack", Warn, "the pack is neither a behavior nor a resource pack";
+    SELF_DEPENDENCY = "self-dependency", Deny, "the pack depends on itself";
+    UNPAIRED_PACKS = "unpaired-packs", Warn, "a behavior and a resource pack shipped together do not reference each other";
+    ONE_WAY_PAIR = "one-way-pair", Allow, "only one pack of a behavior and resource pair references the other";
+    PAIR_VERSION_MISMATCH = "pair-version-mismatch", Deny, "a pack references the other pack of its pair with another version";
+    UNSUPPORTED_FORMAT_VERSION = "unsupported-format-version", Deny, "the format_version is not known";
+    OUTDATED_FORMAT_VERSION = "outdated-format-version", Allow, "the format_version is older than the latest one";
+    FORMAT_VERSION_ARRAY = "format-version-array", Deny, "a version has to be an array in this format_version";
+    FORMAT_VERSION_STRING = "format-version-string", Deny, "a script module version has to be a string in this format_version";
+    MISSING_MIN_ENGINE_VERSION = "missing-min-engine-version", Warn, "the header has no min_engine_version";
+    MIN_ENGINE_VERSION_ARRAY = "min-engine-version-array", Deny, "min_engine_version is not a version array";
+    UNUSED_MIN_ENGINE_VERSION = "unused-min-engine-version", Warn, "min_engine_version is set in format_version 1";
+    SCRIPT_NEEDS_V2 = "script-needs-v2", Deny, "a script module is used in format_version 1";
+}
+
+fn format_rule(issue: &FormatIssue) -> &'static str {
+    match issue {
+        FormatIssue::Unsupported(_) => UNSUPPORTED_FORMAT_VERSION,
+        FormatIssue::ExpectedArray(..) => FORMAT_VERSION_ARRAY,
+        FormatIssue::ExpectedString(_) => FORMAT_VERSION_STRING,
+        FormatIssue::MissingMinEngineVersion(_) => MISSING_MIN_ENGINE_VERSION,
+        FormatIssue::MinEngineVersionNotArray => MIN_ENGINE_VERSION_ARRAY,
+        FormatIssue::UnusedMinEngineVersion => UNUSED_MIN_ENGINE_VERSION,
+        FormatIssue::ScriptNeedsV2 => SCRIPT_NEEDS_V2,
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("unknown lint rule {0}")]
+pub struct UnknownRule(pub String);
+
+/// The severity of every rule, the defaults of [`RULES`] unless changed.
+#[derive(Default, Debug, Clone)]
+pub struct LintConfig {
+    overrides: HashMap<&'static str, Severity>,
+}
+
+impl LintConfig {
+    pub fn set(&mut self, rule: &str, severity: Severity) -> Result<(), UnknownRule> {
+        let rule = RULES
+            .iter()
+            .find(|i| i.id == rule)
+            .ok_or_else(|| UnknownRule(rule.to_string()))?;
+        self.overrides.insert(rule.id, severity);
+        Ok(())
+    }
+
+    pub fn severity(&self, rule: &str) -> Severity {
+        self.overrides.get(rule).copied().unwrap_or_else(|| {
+            RULES
+                .iter()
+                .find(|i| i.id == rule)
+                .map(|i| i.severity)
+                .unwrap_or(Severity::Deny)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    /// The pack the finding is about, as given to [`Linter::lint`].
+    pub pack: String,
+    pub message: String,
+}
+
+/// A manifest to check, `name` tells the user which pack a finding is about.
+pub struct LintTarget {
+    pub name: String,
+    pub manifest: Result<Manifest, String>,
+}
+
+pub struct Linter<'a> {
+    config: &'a LintConfig,
+    findings: Vec<Finding>,
+}
+
+fn is_valid_uuid(uuid: &str) -> bool {
+    uuid::Uuid::try_parse(uuid).is_ok()
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(config: &'a LintConfig) -> Linter<'a> {
+        Linter {
+            config,
+            findings: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, rule: &'static str, pack: &str, message: String) {
+        let severity = self.config.severity(rule);
+        if severity != Severity::Allow {
+            self.findings.push(Finding {
+                rule,
+                severity,
+                pack: pack.to_string(),
+                message,
+            });
+        }
+    }
+
+    /// Check the packs one by one, then the packs that are shipped together against each other.
+    pub fn lint(mut self, targets: &[LintTarget]) -> Vec<Finding> {
+        let mut manifests = Vec::new();
+        for target in targets {
+            match &target.manifest {
+                Ok(manifest) => {
+                    self.lint_manifest(&target.name, manifest);
+                    manifests.push((target.name.as_str(), manifest));
+                }
+                Err(err) => self.report(INVALID_MANIFEST, &target.name, err.clone()),
+            }
+        }
+        self.lint_set(&manifests);
+        self.findings
+    }
+
+    fn lint_manifest(&mut self, name: &str, manifest: &Manifest) {
+        let header = &manifest.header;
+        if header.name.trim().is_empty() {
+            self.report(EMPTY_NAME, name, "header.name is empty".to_string());
+        }
+        if header.description.is_none() {
+            self.report(
+                MISSING_DESCRIPTION,
+                name,
+                "header.description is missing".to_string(),
+            );
+        }
+
+        let mut uuids = vec![("header.uuid".to_string(), &header.uuid)];
+        if manifest.modules.is_empty() {
+            self.report(NO_MODULES, name, "modules is empty".to_string());
+        }
+        for (index, module) in manifest.modules.iter().enumerate() {
+            match &module.uuid {
+                Some(uuid) => uuids.push((format!("modules[{index}].uuid"), uuid)),
+                None => self.report(
+                    MISSING_MODULE_UUID,
+                    name,
+                    format!("modules[{index}] has no uuid"),
+                ),
+            }
+            if module.version.is_none() {
+                self.report(
+                    MISSING_MODULE_VERSION,
+                    name,
+                    format!("modules[{index}] has no version"),
+                );
+            }
+        }
+        for (field, uuid) in &uuids {
+            if !is_valid_uuid(uuid) {
+                self.report(
+                    INVALID_UUID,
+                    name,
+                    format!("{field} {uuid} is not a valid uuid"),
+                );
+            }
+        }
+        for (index, (field, uuid)) in uuids.iter().enumerate() {
+            if let Some((other, _)) = uuids[..index]
+                .iter()
+                .find(|(_, i)| i.eq_ignore_ascii_case(uuid))
+            {
+                self.report(REUSED_UUID, name, format!("{field} is the same as {other}"));
+            }
+        }
+
+        for dependency in manifest.dependencies.iter().flatten() {
+            if let Some(uuid) = &dependency.uuid {
+                if !is_valid_uuid(uuid) {
+                    self.report(
+                        INVALID_UUID,
+                        name,
+                        format!("the dependency on {uuid} is not a valid uuid"),
+                    );
+                }
+                if uuid.eq_ignore_ascii_case(&header.uuid) {
+                    self.report(
+                        SELF_DEPENDENCY,
+                        name,
+                        "the pack depends on itself".to_string(),
+                    );
+                }
+            }
+        }
+
+        let types = manifest.get_type();
+        if types.contains(&ModuleType::Resources)
+            && (types.contains(&ModuleType::Data) || types.contains(&ModuleType::Script))
+        {
+            self.report(
+                MIXED_MODULE_TYPES,
+                name,
+                "the pack has both resources and data or script modules".to_string(),
+            );
+        } else if !types.is_empty() && PackateType::try_from(manifest).is_err() {
+            self.report(
+                NOT_A_PACK,
+                name,
+                format!(
+                    "the modules ({}) are neither a behavior nor a resource pack",
+                    types
+                        .iter()
+                        .map(|i| i.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            );
+        }
+
+        if manifest.format_version().is_outdated() {
+            self.report(
+                OUTDATED_FORMAT_VERSION,
+                name,
+                format!(
+                    "format_version {} is older than {}",
+                    manifest.format_version(),
+                    format::FormatVersion::LATEST
+                ),
+            );
+        }
+        for issue in format::check(manifest) {
+            self.report(format_rule(&issue), name, issue.to_string());
+        }
+    }
+
+    fn lint_set(&mut self, manifests: &[(&str, &Manifest)]) {
+        for (index, (name, manifest)) in manifests.iter().enumerate() {
+            if let Some((other, _)) = manifests[..index]
+                .iter()
+                .find(|(_, i)| i.header.uuid.eq_ignore_ascii_case(&manifest.header.uuid))
+            {
+                self.report(
+                    REUSED_UUID,
+                    name,
+                    format!("header.uuid is the same as the one of {other}"),
+                );
+            }
+        }
+
+        let of_type = |packate_type: PackateType| {
+            manifests
+                .iter()
+                .filter(|(_, i)| PackateType::try_from(*i).is_ok_and(|i| i == packate_type))
+                .collect::<Vec<_>>()
+        };
+        let (behavior, resource) = (
+            of_type(PackateType::Behavior),
+            of_type(PackateType::Resource),
+        );
+        for (bp_name, bp) in &behavior {
+            for (rp_name, rp) in &resource {
+                let to_rp = self.check_reference(bp_name, bp, rp);
+                let to_bp = self.check_reference(rp_name, rp, bp);
+                match (to_rp, to_bp) {
+                    // only a single pair is expected to reference each other
+                    (false, false) if behavior.len() == 1 && resource.len() == 1 => self.report(
+                        UNPAIRED_PACKS,
+                        bp_name,
+                        format!("{bp_name} and {rp_name} do not depend on each other"),
+                    ),
+                    (true, false) => self.report(
+                        ONE_WAY_PAIR,
+                        rp_name,
+                        format!("{rp_name} does not depend on {bp_name}"),
+                    ),
+                    (false, true) => self.report(
+                        ONE_WAY_PAIR,
+                        bp_name,
+                        format!("{bp_name} does not depend on {rp_name}"),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Whether `pack` depends on `other`, reporting a dependency on a version `other` does not have.
+    fn check_reference(&mut self, name: &str, pack: &Manifest, other: &Manifest) -> bool {
+        let Some(dependency) = pack
+            .dependencies
+            .iter()
+            .flatten()
+            .find(|i| i.uuid.as_ref() == Some(&other.header.uuid))
+        else {
+            return false;
+        };
+        if dependency.version != other.header.version {
+            self.report(
+                PAIR_VERSION_MISMATCH,
+                name,
+                format!(
+                    "depends on {} {} but the pack shipped with it is {}",
+                    other.header.name, dependency.version, other.header.version
+                ),
+            );
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    const BP: &str = "11111111-1111-4111-8111-111111111111";
+    const RP: &str = "22222222-2222-4222-8222-222222222222";
+    const OTHER: &str = "33333333-3333-4333-8333-333333333333";
+
+    /// A manifest of format_version 3 every rule accepts, with one module of `module_type`.
+    fn pack(uuid: &str, module_type: &str) -> Value {
+        json!({
+            "format_version": 3,
+            "header": {
+                "name": "x",
+                "description": "x",
+                "uuid": uuid,
+                "version": [1, 0, 0],
+                "min_engine_version": [1, 21, 0]
+            },
+            "modules": [{
+                "type": module_type,
+                "uuid": format!("{}9", &uuid[..35]),
+                "version": [1, 0, 0]
+            }]
+        })
+    }
+
+    fn lint_with(config: &LintConfig, packs: &[Value]) -> Vec<Finding> {
+        let targets = packs
+            .iter()
+            .enumerate()
+            .map(|(index, i)| LintTarget {
+                name: format!("pack{index}"),
+                manifest: Manifest::new(i.to_string()).map_err(|err| err.to_string()),
+            })
+            .collect::<Vec<_>>();
+        Linter::new(config).lint(&targets)
+    }
+
+    /// The ids of the rules reported for `packs` with the default config.
+    fn rules(packs: &[Value]) -> Vec<&'static str> {
+        lint_with(&LintConfig::default(), packs)
+            .iter()
+            .map(|i| i.rule)
+            .collect()
+    }
+
+    fn depend(pack: &mut Value, uuid: &str, version: Value) {
+        pack["dependencies"] = json!([{"uuid": uuid, "version": version}]);
+    }
+
+    fn pair() -> [Value; 2] {
+        let (mut bp, mut rp) = (pack(BP, "data"), pack(RP, "resources"));
+        depend(&mut bp, RP, json!([1, 0, 0]));
+        depend(&mut rp, BP, json!([1, 0, 0]));
+        [bp, rp]
+    }
+
+    #[test]
+    fn rule_ids() {
+        let ids = RULES.iter().map(|i| (i.id, i.severity)).collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                ("invalid-manifest", Severity::Deny),
+                ("invalid-uuid", Severity::Deny),
+                ("reused-uuid", Severity::Deny),
+                ("empty-name", Severity::Deny),
+                ("missing-description", Severity::Warn),
+                ("no-modules", Severity::Deny),
+                ("missing-module-uuid", Severity::Deny),
+                ("missing-module-version", Severity::Deny),
+                ("mixed-module-types", Severity::Deny),
+                ("not-a-pack", Severity::Warn),
+                ("self-dependency", Severity::Deny),
+                ("unpaired-packs", Severity::Warn),
+                ("one-way-pair", Severity::Allow),
+                ("pair-version-mismatch", Severity::Deny),
+                ("unsupported-format-version", Severity::Deny),
+                ("outdated-format-version", Severity::Allow),
+                ("format-version-array", Severity::Deny),
+                ("format-version-string", Severity::Deny),
+                ("missing-min-engine-version", Severity::Warn),
+                ("min-engine-version-array", Severity::Deny),
+                ("unused-min-engine-version", Severity::Warn),
+                ("script-needs-v2", Severity::Deny),
+            ]
+        );
+    }
+
+    #[test]
+    fn valid() {
+        assert!(rules(&[pack(BP, "data")]).is_empty());
+        assert!(rules(&[pack(RP, "resources")]).is_empty());
+        assert!(rules(&pair()).is_empty());
+    }
+
+    #[test]
+    fn header() {
+        let mut bp = pack(BP, "data");
+        bp["header"]["name"] = json!(" ");
+        assert_eq!(rules(&[bp]), [EMPTY_NAME]);
+
+        let mut bp = pack(BP, "data");
+        bp["header"].as_object_mut().unwrap().remove("description");
+        assert_eq!(rules(&[bp]), [MISSING_DESCRIPTION]);
+
+        let mut bp = pack(BP, "data");
+        bp["header"]["uuid"] = json!("not a uuid");
+        assert_eq!(rules(&[bp]), [INVALID_UUID]);
+
+        let bp = json!({"header": {"name": "x"}});
+        assert_eq!(rules(&[bp]), [INVALID_MANIFEST]);
+    }
+
+    #[test]
+    fn modules() {
+        let mut bp = pack(BP, "data");
+        bp["modules"] = json!([]);
+        assert_eq!(rules(&[bp]), [NO_MODULES]);
+
+        let mut bp = pack(BP, "data");
+        bp["modules"][0].as_object_mut().unwrap().remove("uuid");
+        assert_eq!(rules(&[bp]), [MISSING_MODULE_UUID]);
+
+        let mut bp = pack(BP, "data");
+        bp["modules"][0].as_object_mut().unwrap().remove("version");
+        assert_eq!(rules(&[bp]), [MISSING_MODULE_VERSION]);
+
+        let mut bp = pack(BP, "data");
+        bp["modules"][0]["uuid"] = json!(BP.to_uppercase());
+        assert_eq!(rules(&[bp]), [REUSED_UUID]);
+
+        let mut bp = pack(BP, "data");
+        let module = bp["modules"][0].clone();
+        bp["modules"] = json!([module, {"type": "resources", "uuid": RP, "version": [1, 0, 0]}]);
+        assert_eq!(rules(&[bp]), [MIXED_MODULE_TYPES]);
+
+        assert_eq!(rules(&[pack(BP, "skin_pack")]), [NOT_A_PACK]);
+    }
+
+    #[test]
+    fn dependencies() {
+        let mut bp = pack(BP, "data");
+        depend(&mut bp, BP, json!([1, 0, 0]));
+        assert_eq!(rules(&[bp]), [SELF_DEPENDENCY]);
+
+        let mut bp = pack(BP, "data");
+        depend(&mut bp, "not a uuid", json!([1, 0, 0]));
+        assert_eq!(rules(&[bp]), [INVALID_UUID]);
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(rules(&[pack(BP, "data"), pack(BP, "data")]), [REUSED_UUID]);
+        assert_eq!(
+            rules(&[pack(BP, "data"), pack(RP, "resources")]),
+            [UNPAIRED_PACKS]
+        );
+        // with more than one pack of a type, not every pair has to reference each other
+        let packs = [pack(BP, "data"), pack(OTHER, "data"), pack(RP, "resources")];
+        assert!(rules(&packs).is_empty());
+
+        let [bp, mut rp] = pair();
+        rp.as_object_mut().unwrap().remove("dependencies");
+        assert!(rules(&[bp, rp]).is_empty());
+
+        let [mut bp, rp] = pair();
+        depend(&mut bp, RP, json!([2, 0, 0]));
+        let findings = lint_with(&LintConfig::default(), &[bp, rp]);
+        assert!(matches!(
+            &findings[..],
+            [Finding { rule: PAIR_VERSION_MISMATCH, pack, .. }] if pack == "pack0"
+        ));
+    }
+
+    #[test]
+    fn format_version() {
+        let mut bp = pack(BP, "data");
+        bp["format_version"] = json!(9);
+        assert_eq!(rules(&[bp]), [UNSUPPORTED_FORMAT_VERSION]);
+
+        let mut bp = pack(BP, "data");
+        bp["format_version"] = json!(2);
+        bp["header"]["version"] = json!("1.0.0");
+        assert_eq!(rules(&[bp]), [FORMAT_VERSION_ARRAY]);
+
+        let mut bp = pack(BP, "script");
+        bp["dependencies"] = json!([{"module_name": "@minecraft/server", "version": [1, 8, 0]}]);
+        assert_eq!(rules(&[bp]), [FORMAT_VERSION_STRING]);
+
+        let mut bp = pack(BP, "data");
+        bp["header"]
+            .as_object_mut()
+            .unwrap()
+            .remove("min_engine_version");
+        assert_eq!(rules(&[bp]), [MISSING_MIN_ENGINE_VERSION]);
+
+        let mut bp = pack(BP, "data");
+        bp["header"]["min_engine_version"] = json!("1.21.0");
+        assert_eq!(rules(&[bp]), [MIN_ENGINE_VERSION_ARRAY]);
+
+        let mut bp = pack(BP, "data");
+        bp["format_version"] = json!(1);
+        assert_eq!(rules(&[bp]), [UNUSED_MIN_ENGINE_VERSION]);
+
+        let mut bp = pack(BP, "script");
+        bp["format_version"] = json!(1);
+        bp["header"]
+            .as_object_mut()
+            .unwrap()
+            .remove("min_engine_version");
+        assert_eq!(rules(&[bp]), [SCRIPT_NEEDS_V2]);
+    }
+
+    #[test]
+    fn config() {
+        let mut bp = pack(OTHER, "data");
+        bp["format_version"] = json!(2);
+        bp["header"].as_object_mut().unwrap().remove("description");
+        let [pair_bp, mut pair_rp] = pair();
+        pair_rp.as_object_mut().unwrap().remove("dependencies");
+        let packs = [bp, pair_bp, pair_rp];
+        assert_eq!(rules(&packs), [MISSING_DESCRIPTION]);
+
+        let mut config = LintConfig::default();
+        config.set(MISSING_DESCRIPTION, Severity::Allow).unwrap();
+        config.set(OUTDATED_FORMAT_VERSION, Severity::Deny).unwrap();
+        config.set(ONE_WAY_PAIR, Severity::Warn).unwrap();
+        let findings = lint_with(&config, &packs)
+            .into_iter()
+            .map(|i| (i.rule, i.severity))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            findings,
+            [
+                (OUTDATED_FORMAT_VERSION, Severity::Deny),
+                (ONE_WAY_PAIR, Severity::Warn)
+            ]
+        );
+        assert_eq!(config.severity(MISSING_DESCRIPTION), Severity::Allow);
+        assert_eq!(config.severity(UNPAIRED_PACKS), Severity::Warn);
+
+        assert!(config.set("no-such-rule", Severity::Warn).is_err());
+    }
+}
@@ -1,17 +1,147 @@
 use super::AddonVersion;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::BTreeMap;
+pub mod format;
 pub mod version;
 
-#[derive(Serialize, Deserialize)]
+use format::FormatVersion;
+
+/// The `manifest.json` of a pack, see
+/// <https://learn.microsoft.com/minecraft/creator/reference/content/addonsreference/packmanifest>.
+///
+/// Every struct keeps the fields it does not model in `extra`, so that rewriting a manifest does
+/// not lose them. The optional sections and the optional fields of the header and the modules
+/// that can not be read are kept in `extra` as they are.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(from = "RawManifest")]
+pub struct Manifest {
+    /// Left as it is when rewriting the manifest, see [`Manifest::format_version`] for the
+    /// version the manifest is read as.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format_version: Option<FormatVersion>,
+    pub header: Header,
+    pub modules: Vec<Module>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<Dependency>>,
+    /// Optional features the pack uses, such as `chemistry`, `raytraced` or `script_eval`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+    /// Options shown to the player in the pack settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<Vec<Setting>>,
+    /// Variants of a resource pack the player can choose between.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subpacks: Option<Vec<Subpack>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The sections of a manifest a pack can not do without, see [`Manifest`].
+#[derive(Deserialize)]
+struct RawManifest {
+    header: Header,
+    modules: Vec<Module>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+/// Take the section or field `key` out of `extra`, leaving it there when it can not be read.
+fn take_section<T: DeserializeOwned>(extra: &mut Map<String, Value>, key: &str) -> Option<T> {
+    let value = extra.get(key)?;
+    let section = T::deserialize(value).ok()?;
+    extra.remove(key);
+    Some(section)
+}
+
+impl From<RawManifest> for Manifest {
+    fn from(mut raw: RawManifest) -> Self {
+        Manifest {
+            format_version: take_section(&mut raw.extra, "format_version"),
+            header: raw.header,
+            modules: raw.modules,
+            dependencies: take_section(&mut raw.extra, "dependencies"),
+            capabilities: take_section(&mut raw.extra, "capabilities"),
+            metadata: take_section(&mut raw.extra, "metadata"),
+            settings: take_section(&mut raw.extra, "settings"),
+            subpacks: take_section(&mut raw.extra, "subpacks"),
+            extra: raw.extra,
+        }
+    }
+}
+
+/// The fields of a header a pack can not do without, see [`Manifest`].
+#[derive(Deserialize)]
+struct RawHeader {
+    name: String,
+    uuid: String,
+    version: AddonVersion,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl From<RawHeader> for Header {
+    fn from(mut raw: RawHeader) -> Self {
+        Header {
+            name: raw.name,
+            description: take_section(&mut raw.extra, "description"),
+            uuid: raw.uuid,
+            version: raw.version,
+            min_engine_version: take_section(&mut raw.extra, "min_engine_version"),
+            base_game_version: take_section(&mut raw.extra, "base_game_version"),
+            lock_template_options: take_section(&mut raw.extra, "lock_template_options"),
+            allow_random_seed: take_section(&mut raw.extra, "allow_random_seed"),
+            platform_locked: take_section(&mut raw.extra, "platform_locked"),
+            pack_scope: take_section(&mut raw.extra, "pack_scope"),
+            extra: raw.extra,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(from = "RawHeader")]
 pub struct Header {
-    pub description: Option<String>,
+    /// The name shown in the game, may be a key of the pack's `.lang` files.
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     pub uuid: String,
-    // The docs say it might be SemVer String here, maybe it needs to be supported, note that all `version` fields.
     pub version: AddonVersion,
-    // and more ...
+    /// The oldest game version the pack is written for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_engine_version: Option<AddonVersion>,
+    /// The game version a world template runs on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_game_version: Option<AddonVersion>,
+    /// Whether the world options of a world template can not be changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_template_options: Option<bool>,
+    /// Whether a world template lets the player change the seed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_random_seed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform_locked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_scope: Option<PackScope>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+
+/// Where a resource pack can be applied.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PackScope {
+    World,
+    Global,
+    Any,
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// The kind of a module, the ones this tool does not know are kept in `Other`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub enum ModuleType {
     #[serde(rename = "resources")]
     Resources,
@@ -21,34 +151,183 @@ pub enum ModuleType {
     WorldTemplate,
     #[serde(rename = "script")]
     Script,
+    #[serde(rename = "skin_pack")]
+    SkinPack,
+    /// Deprecated, used by the old scripting api.
+    #[serde(rename = "client_data")]
+    ClientData,
+    /// Deprecated, used by the old scripting api.
+    #[serde(rename = "javascript")]
+    Javascript,
+    #[serde(rename = "interface")]
+    Interface,
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl ModuleType {
+    /// The name used in manifests.
+    pub fn name(&self) -> &str {
+        match self {
+            ModuleType::Resources => "resources",
+            ModuleType::Data => "data",
+            ModuleType::WorldTemplate => "world_template",
+            ModuleType::Script => "script",
+            ModuleType::SkinPack => "skin_pack",
+            ModuleType::ClientData => "client_data",
+            ModuleType::Javascript => "javascript",
+            ModuleType::Interface => "interface",
+            ModuleType::Other(name) => name,
+        }
+    }
+}
+
+/// The fields of a module a pack can not do without, see [`Manifest`].
+#[derive(Deserialize)]
+struct RawModule {
+    #[serde(rename = "type")]
+    module_type: ModuleType,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
 }
 
-#[derive(Serialize, Deserialize)]
+impl From<RawModule> for Module {
+    fn from(mut raw: RawModule) -> Self {
+        Module {
+            module_type: raw.module_type,
+            uuid: take_section(&mut raw.extra, "uuid"),
+            version: take_section(&mut raw.extra, "version"),
+            description: take_section(&mut raw.extra, "description"),
+            entry: take_section(&mut raw.extra, "entry"),
+            language: take_section(&mut raw.extra, "language"),
+            extra: raw.extra,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(from = "RawModule")]
 pub struct Module {
     #[serde(rename = "type")]
-    pub modles_type: ModuleType, // and more ...
+    pub module_type: ModuleType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<AddonVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The script file run by a script module, relative to the pack root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry: Option<String>,
+    /// The language of a script module, `javascript`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct Dependencie {
+/// Another pack, by `uuid`, or a script module, by `module_name`, the pack needs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Dependency {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub uuid: Option<String>,
+    /// A script module such as `@minecraft/server`.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub module_name: Option<String>,
     pub version: AddonVersion,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Metadata {
-    pub authors: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Authors>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<String>,
-    pub url: Option<String>, // and more ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The tools used to make the pack and their versions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_with: Option<BTreeMap<String, Vec<String>>>,
+    /// `addon` for packs that are part of an add-on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_type: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct Manifest {
-    pub header: Header,
-    pub modules: Vec<Module>,
-    pub dependencies: Option<Vec<Dependencie>>,
-    pub matedata: Option<Metadata>, // and more ...
+/// The authors of a pack, a list in most manifests but a single string in some.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum Authors {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Authors {
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            Authors::One(author) => std::slice::from_ref(author),
+            Authors::Many(authors) => authors,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingType {
+    Label,
+    Toggle,
+    Slider,
+    Dropdown,
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// An entry of the pack settings, only the fields of its type are set.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Setting {
+    #[serde(rename = "type")]
+    pub setting_type: SettingType,
+    /// The text shown to the player, may be a key of the pack's `.lang` files.
+    pub text: String,
+    /// The name scripts read the value by, every type but labels has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    /// The choices of a dropdown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<SettingOption>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SettingOption {
+    pub name: String,
+    pub text: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Subpack {
+    /// The folder in `subpacks` holding the files of the subpack.
+    pub folder_name: String,
+    pub name: String,
+    /// The memory the device needs for the subpack, in steps of 0.25 GB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_tier: Option<Number>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl Manifest {
@@ -56,8 +335,24 @@ impl Manifest {
         serde_jsonc::from_str(str.as_ref())
     }
 
+    /// The format version the manifest is read as, the default one when it has none.
+    ///
+    /// A `format_version` that is not a number is kept in `extra` as it is written. A numeric
+    /// string is read as its number, anything else as unknown.
+    pub fn format_version(&self) -> FormatVersion {
+        match (self.format_version, self.extra.get("format_version")) {
+            (Some(version), _) => version,
+            (None, None) => FormatVersion::default(),
+            (None, Some(raw)) => raw
+                .as_str()
+                .and_then(|i| i.trim().parse::<u32>().ok())
+                .map(FormatVersion::from)
+                .unwrap_or(FormatVersion::Unknown(0)),
+        }
+    }
+
     pub fn get_type(&self) -> Vec<ModuleType> {
-        self.modules.iter().map(|i| i.modles_type).collect()
+        self.modules.iter().map(|i| i.module_type.clone()).collect()
     }
 
     #[inline]
@@ -71,6 +366,8 @@ impl Manifest {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
 pub enum PackateType {
     Behavior,
     Resource,
@@ -100,6 +397,18 @@ impl PackateType {
             PackateType::Resource => "world_resource_packs.json",
         }
     }
+    pub fn get_history_file_string(&self) -> &str {
+        match self {
+            PackateType::Behavior => "world_behavior_pack_history.json",
+            PackateType::Resource => "world_resource_pack_history.json",
+        }
+    }
+    pub fn get_short_name(&self) -> &str {
+        match self {
+            PackateType::Behavior => "behavior",
+            PackateType::Resource => "resource",
+        }
+    }
     pub fn get_path_name(&self) -> &str {
         match self {
             PackateType::Behavior => "behavior_packs",
@@ -107,3 +416,97 @@ impl PackateType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addon::json_style::JsonStyle;
+
+    #[test]
+    fn round_trip_without_format_version() {
+        let text = r#"{
+  "header": {
+    "name": "x",
+    "uuid": "11111111-1111-4111-8111-111111111111",
+    "version": "1.0.0"
+  },
+  "modules": [
+    {
+      "type": "data",
+      "uuid": "11111111-1111-4111-8111-111111111112",
+      "version": [1, 0, 0]
+    }
+  ]
+}
+"#;
+        let manifest = Manifest::new(text).unwrap();
+        assert_eq!(manifest.format_version, None);
+        assert_eq!(manifest.format_version(), FormatVersion::V1);
+        assert_eq!(JsonStyle::of(text).to_string(&manifest).unwrap(), text);
+    }
+
+    /// A manifest with `header` and `module` added to the header and the module, which must be
+    /// read with the field left in `extra` and written back as it was.
+    fn lenient(header: &str, module: &str) -> (Manifest, String) {
+        let text = format!(
+            r#"{{"format_version":2,"header":{{"name":"x","uuid":"u","version":[1,0,0]{header}}},"modules":[{{"type":"data","uuid":"m"{module}}}]}}"#
+        );
+        let manifest = Manifest::new(&text).unwrap();
+        assert_eq!(JsonStyle::of(&text).to_string(&manifest).unwrap(), text);
+        (manifest, text)
+    }
+
+    #[test]
+    fn lenient_module_version() {
+        let (manifest, _) = lenient("", r#","version":[1,0]"#);
+        assert!(manifest.modules[0].version.is_none());
+        assert_eq!(
+            manifest.modules[0].extra["version"],
+            serde_json::json!([1, 0])
+        );
+    }
+
+    #[test]
+    fn lenient_min_engine_version() {
+        let (manifest, _) = lenient(r#","min_engine_version":[1,16]"#, "");
+        assert!(manifest.header.min_engine_version.is_none());
+        assert!(manifest.header.extra.contains_key("min_engine_version"));
+    }
+
+    #[test]
+    fn lenient_base_game_version() {
+        let (manifest, _) = lenient(r#","base_game_version":"*""#, "");
+        assert!(manifest.header.base_game_version.is_none());
+        assert_eq!(manifest.header.extra["base_game_version"], "*");
+    }
+
+    #[test]
+    fn lenient_lock_template_options() {
+        let (manifest, _) = lenient(r#","lock_template_options":"true""#, "");
+        assert!(manifest.header.lock_template_options.is_none());
+        assert_eq!(manifest.header.extra["lock_template_options"], "true");
+    }
+
+    #[test]
+    fn lenient_module_description() {
+        let (manifest, _) = lenient("", r#","description":1"#);
+        assert!(manifest.modules[0].description.is_none());
+        assert_eq!(manifest.modules[0].extra["description"], 1);
+    }
+
+    #[test]
+    fn readable_fields_are_taken() {
+        let (manifest, _) = lenient(
+            r#","min_engine_version":[1,20,0],"pack_scope":"galaxy""#,
+            r#","version":"1.0.0""#,
+        );
+        assert!(manifest.header.min_engine_version.is_some());
+        assert_eq!(
+            manifest.header.pack_scope,
+            Some(PackScope::Other("galaxy".to_string()))
+        );
+        assert!(manifest.header.extra.is_empty());
+        assert!(manifest.modules[0].version.is_some());
+        assert!(manifest.modules[0].extra.is_empty());
+    }
+}
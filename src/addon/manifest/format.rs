@@ -0,0 +1,262 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{version::AddonVersion, Manifest, ModuleType};
+
+/// The `format_version` of a manifest, which decides the rules the rest of the manifest follows.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(from = "u32", into = "u32")]
+pub enum FormatVersion {
+    /// Manifests without a `format_version` are read as version 1.
+    #[default]
+    V1,
+    V2,
+    V3,
+    /// A number this tool does not know, 0 for a value that is not a number.
+    Unknown(u32),
+}
+
+impl FormatVersion {
+    pub const LATEST: FormatVersion = FormatVersion::V3;
+
+    pub fn is_outdated(&self) -> bool {
+        u32::from(*self) < u32::from(FormatVersion::LATEST)
+    }
+}
+
+impl From<u32> for FormatVersion {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => FormatVersion::V1,
+            2 => FormatVersion::V2,
+            3 => FormatVersion::V3,
+            other => FormatVersion::Unknown(other),
+        }
+    }
+}
+
+impl From<FormatVersion> for u32 {
+    fn from(value: FormatVersion) -> Self {
+        match value {
+            FormatVersion::V1 => 1,
+            FormatVersion::V2 => 2,
+            FormatVersion::V3 => 3,
+            FormatVersion::Unknown(other) => other,
+        }
+    }
+}
+
+impl Display for FormatVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", u32::from(*self))
+    }
+}
+
+/// A part of a manifest that breaks the rules of its format version.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum FormatIssue {
+    #[error("format_version {0} is not supported")]
+    Unsupported(String),
+    #[error("{0} must be a version array in format_version {1}")]
+    ExpectedArray(String, FormatVersion),
+    #[error("the dependency on {0} must be a semver string in format_version 3")]
+    ExpectedString(String),
+    #[error("min_engine_version is required in format_version {0}")]
+    MissingMinEngineVersion(FormatVersion),
+    #[error("min_engine_version must be a version array")]
+    MinEngineVersionNotArray,
+    #[error("min_engine_version is not read in format_version 1")]
+    UnusedMinEngineVersion,
+    #[error("script modules need format_version 2 or later")]
+    ScriptNeedsV2,
+}
+
+fn is_array(version: &AddonVersion) -> bool {
+    matches!(version, AddonVersion::Arr(_))
+}
+
+/// Check a manifest against the rules of its format version.
+///
+/// * 1: every version is an array, there is no `min_engine_version` and no script module.
+/// * 2: versions are arrays, except for the dependencies on script modules which may be semver
+///   strings, and `min_engine_version` is required.
+/// * 3: versions may be semver strings, and the dependencies on script modules have to be.
+pub fn check(manifest: &Manifest) -> Vec<FormatIssue> {
+    let format = manifest.format_version();
+    let header = &manifest.header;
+    let mut out = Vec::new();
+    if let FormatVersion::Unknown(other) = format {
+        // the value as it is written, which may not be a number
+        let raw = manifest.extra.get("format_version").map(|i| i.to_string());
+        out.push(FormatIssue::Unsupported(raw.unwrap_or(other.to_string())));
+        return out;
+    }
+
+    if matches!(format, FormatVersion::V1 | FormatVersion::V2) {
+        let mut versions = vec![("header.version".to_string(), &header.version)];
+        versions.extend(
+            manifest
+                .modules
+                .iter()
+                .enumerate()
+                .filter_map(|(index, i)| {
+                    i.version
+                        .as_ref()
+                        .map(|version| (format!("modules[{index}].version"), version))
+                }),
+        );
+        versions.extend(
+            manifest
+                .dependencies
+                .iter()
+                .flatten()
+                .filter_map(|i| i.uuid.as_ref().map(|uuid| (uuid, &i.version)))
+                .map(|(uuid, version)| (format!("the dependency on {uuid}"), version)),
+        );
+        out.extend(
+            versions
+                .into_iter()
+                .filter(|(_, version)| !is_array(version))
+                .map(|(field, _)| FormatIssue::ExpectedArray(field, format)),
+        );
+    }
+    if format == FormatVersion::V1 {
+        // script modules and their dependencies came with format_version 2
+        if manifest.get_type().contains(&ModuleType::Script) {
+            out.push(FormatIssue::ScriptNeedsV2);
+        }
+        out.extend(
+            manifest
+                .dependencies
+                .iter()
+                .flatten()
+                .filter(|i| i.module_name.is_some() && !is_array(&i.version))
+                .map(|i| {
+                    FormatIssue::ExpectedArray(
+                        format!(
+                            "the dependency on {}",
+                            i.module_name.as_deref().unwrap_or("")
+                        ),
+                        format,
+                    )
+                }),
+        );
+    }
+    if format == FormatVersion::V3 {
+        out.extend(
+            manifest
+                .dependencies
+                .iter()
+                .flatten()
+                .filter_map(|i| i.module_name.as_ref().filter(|_| is_array(&i.version)))
+                .map(|name| FormatIssue::ExpectedString(name.clone())),
+        );
+    }
+
+    match (&header.min_engine_version, format) {
+        (Some(_), FormatVersion::V1) => out.push(FormatIssue::UnusedMinEngineVersion),
+        (None, FormatVersion::V2 | FormatVersion::V3) => {
+            out.push(FormatIssue::MissingMinEngineVersion(format))
+        }
+        (Some(version), _) if !is_array(version) => out.push(FormatIssue::MinEngineVersionNotArray),
+        _ => {}
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_str(str: &str) -> Vec<FormatIssue> {
+        check(&Manifest::new(str).unwrap())
+    }
+
+    fn manifest(format_version: &str, header: &str, dependencies: &str) -> String {
+        format!(
+            r#"{{
+                {format_version}
+                "header": {{"name": "x", "uuid": "u", "version": [1, 0, 0] {header}}},
+                "modules": [{{"type": "script", "uuid": "m", "version": [1, 0, 0]}}],
+                "dependencies": [{dependencies}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn valid() {
+        assert!(check_str(&manifest(
+            r#""format_version": 2,"#,
+            r#", "min_engine_version": [1, 20, 0]"#,
+            r#"{"module_name": "@minecraft/server", "version": "1.8.0"}"#
+        ))
+        .is_empty());
+        assert!(check_str(&manifest(
+            r#""format_version": 3,"#,
+            r#", "min_engine_version": [1, 21, 0]"#,
+            r#"{"module_name": "@minecraft/server", "version": "1.8.0"}"#
+        ))
+        .is_empty());
+    }
+
+    #[test]
+    fn missing_format_version_is_v1() {
+        let issues = check_str(&manifest("", "", ""));
+        assert!(matches!(issues[..], [FormatIssue::ScriptNeedsV2]));
+    }
+
+    #[test]
+    fn issues() {
+        let issues = check_str(&manifest(
+            r#""format_version": 2,"#,
+            "",
+            r#"{"uuid": "d", "version": "1.0.0"}"#,
+        ));
+        assert!(matches!(
+            &issues[..],
+            [
+                FormatIssue::ExpectedArray(field, FormatVersion::V2),
+                FormatIssue::MissingMinEngineVersion(FormatVersion::V2)
+            ] if field == "the dependency on d"
+        ));
+        let issues = check_str(&manifest(
+            r#""format_version": 3,"#,
+            r#", "min_engine_version": "1.21.0""#,
+            r#"{"module_name": "@minecraft/server", "version": [1, 8, 0]}"#,
+        ));
+        assert!(matches!(
+            &issues[..],
+            [
+                FormatIssue::ExpectedString(name),
+                FormatIssue::MinEngineVersionNotArray
+            ] if name == "@minecraft/server"
+        ));
+        let issues = check_str(&manifest(r#""format_version": 9,"#, "", ""));
+        assert!(matches!(&issues[..], [FormatIssue::Unsupported(raw)] if raw == "9"));
+    }
+
+    #[test]
+    fn string_format_version() {
+        let text = manifest(
+            r#""format_version": "2","#,
+            r#", "min_engine_version": [1, 20, 0]"#,
+            "",
+        );
+        let parsed = Manifest::new(&text).unwrap();
+        assert_eq!(parsed.format_version, None);
+        assert_eq!(parsed.format_version(), FormatVersion::V2);
+        assert!(check(&parsed).is_empty());
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap()["format_version"],
+            "2"
+        );
+
+        let parsed = Manifest::new(manifest(r#""format_version": "two","#, "", "")).unwrap();
+        assert_eq!(parsed.format_version(), FormatVersion::Unknown(0));
+        assert!(matches!(
+            &check(&parsed)[..],
+            [FormatIssue::Unsupported(raw)] if raw == r#""two""#
+        ));
+    }
+}
@@ -1,6 +1,6 @@
-use std::{cmp::Ordering, hash::Hash};
+use std::{cmp::Ordering, fmt::Display, hash::Hash, str::FromStr};
 
-use semver::Version;
+use semver::{Comparator, Op, Version, VersionReq};
 use serde::{de, de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
@@ -9,9 +9,9 @@ pub enum AddonVersion {
     Arr([u64; 3]),
 }
 
-impl ToString for AddonVersion {
-    fn to_string(&self) -> String {
-        Version::from(self).to_string()
+impl Display for AddonVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Version::from(self))
     }
 }
 
@@ -97,3 +97,281 @@ impl Serialize for SemVerStr {
         serializer.serialize_str(&self.inner.to_string())
     }
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseVersionError {
+    #[error("invalid version array {0}, expected something like [1, 2, 3]")]
+    Array(String),
+    #[error(transparent)]
+    SemVer(#[from] semver::Error),
+}
+
+/// Parse `[1,2,3]` as the array form and anything else as a semver string.
+fn parse_array(str: &str) -> Option<Result<[u64; 3], ParseVersionError>> {
+    let inner = str.strip_prefix('[')?.strip_suffix(']')?;
+    Some(
+        serde_json::from_str::<[u64; 3]>(&format!("[{inner}]"))
+            .map_err(|_| ParseVersionError::Array(str.to_string())),
+    )
+}
+
+impl FromStr for AddonVersion {
+    type Err = ParseVersionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match parse_array(s) {
+            Some(arr) => Ok(AddonVersion::Arr(arr?)),
+            None => Ok(AddonVersion::Str(SemVerStr {
+                inner: Version::parse(s)?,
+            })),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{0} has a prerelease or build, which a version array can not hold")]
+pub struct NotAnArray(pub Version);
+
+impl AddonVersion {
+    /// `version` written in the same form as this one, an array or a semver string.
+    pub fn with_form_of(&self, version: Version) -> Result<AddonVersion, NotAnArray> {
+        match self {
+            AddonVersion::Str(_) => Ok(version.into()),
+            AddonVersion::Arr(_) if version.pre.is_empty() && version.build.is_empty() => {
+                Ok(AddonVersion::Arr([
+                    version.major,
+                    version.minor,
+                    version.patch,
+                ]))
+            }
+            AddonVersion::Arr(_) => Err(NotAnArray(version)),
+        }
+    }
+}
+
+/// How to change the version of a pack for a new release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+    To(Version),
+}
+
+impl Bump {
+    /// The next version, the prerelease and build of `version` are dropped.
+    pub fn apply(&self, version: &AddonVersion) -> Version {
+        let version = Version::from(version);
+        match self {
+            Bump::Major => Version::new(version.major + 1, 0, 0),
+            Bump::Minor => Version::new(version.major, version.minor + 1, 0),
+            Bump::Patch => Version::new(version.major, version.minor, version.patch + 1),
+            Bump::To(version) => version.clone(),
+        }
+    }
+}
+
+/// `major`, `minor`, `patch`, or the version to set, as `1.2.3` or `[1,2,3]`.
+impl FromStr for Bump {
+    type Err = ParseVersionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "major" => Ok(Bump::Major),
+            "minor" => Ok(Bump::Minor),
+            "patch" => Ok(Bump::Patch),
+            other => Ok(Bump::To(Version::from(&other.parse::<AddonVersion>()?))),
+        }
+    }
+}
+
+impl From<Version> for AddonVersion {
+    fn from(value: Version) -> Self {
+        AddonVersion::Str(SemVerStr { inner: value })
+    }
+}
+
+/// A range of versions, both forms of [`AddonVersion`] are matched as the semver version they stand
+/// for, so prereleases only match comparators of the same `major.minor.patch` with a prerelease.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddonVersionReq {
+    inner: VersionReq,
+}
+
+impl AddonVersionReq {
+    /// The version or any later one, which is what a version in a manifest dependency means.
+    pub fn at_least(version: &AddonVersion) -> AddonVersionReq {
+        let version = Version::from(version);
+        AddonVersionReq {
+            inner: VersionReq {
+                comparators: vec![Comparator {
+                    op: Op::GreaterEq,
+                    major: version.major,
+                    minor: Some(version.minor),
+                    patch: Some(version.patch),
+                    pre: version.pre,
+                }],
+            },
+        }
+    }
+
+    pub fn matches(&self, version: &AddonVersion) -> bool {
+        self.inner.matches(&Version::from(version))
+    }
+}
+
+/// A bare version, `[1,2,0]` or `1.2.0`, means that version or later as it does in a manifest
+/// dependency, anything else follows the semver rules, e.g. `^1.2.0`, `^1.9.0-beta` or `1.2.*`.
+impl FromStr for AddonVersionReq {
+    type Err = ParseVersionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(arr) = parse_array(s) {
+            return Ok(AddonVersionReq::at_least(&AddonVersion::Arr(arr?)));
+        }
+        match Version::parse(s) {
+            Ok(version) => Ok(AddonVersionReq::at_least(&version.into())),
+            Err(_) => Ok(AddonVersionReq {
+                inner: VersionReq::parse(s)?,
+            }),
+        }
+    }
+}
+
+impl Display for AddonVersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.inner)
+    }
+}
+
+/// A version array or a string, read as by [`AddonVersionReq::from_str`].
+impl<'de> Deserialize<'de> for AddonVersionReq {
+    fn deserialize<D>(deserializer: D) -> Result<AddonVersionReq, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Arr([u64; 3]),
+            Str(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Arr(arr) => Ok(AddonVersionReq::at_least(&AddonVersion::Arr(arr))),
+            Raw::Str(str) => str.parse().map_err(de::Error::custom),
+        }
+    }
+}
+
+impl Serialize for AddonVersionReq {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.inner.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(str: &str) -> AddonVersion {
+        str.parse().unwrap()
+    }
+
+    fn req(str: &str) -> AddonVersionReq {
+        str.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_version() {
+        assert!(matches!(version("[1, 2, 3]"), AddonVersion::Arr([1, 2, 3])));
+        assert!(matches!(version(" [1,2,3] "), AddonVersion::Arr([1, 2, 3])));
+        assert!(matches!(version("1.2.3-beta"), AddonVersion::Str(_)));
+        assert!(matches!(
+            "[1, 2]".parse::<AddonVersion>(),
+            Err(ParseVersionError::Array(_))
+        ));
+        assert!(matches!(
+            "1.2".parse::<AddonVersion>(),
+            Err(ParseVersionError::SemVer(_))
+        ));
+    }
+
+    #[test]
+    fn forms_are_kept() {
+        assert_eq!(version("[1,2,3]"), version("1.2.3"));
+        assert_ne!(version("[1,2,3]"), version("1.2.3-beta"));
+        assert_eq!(
+            serde_json::to_string(&version("[1,2,3]")).unwrap(),
+            "[1,2,3]"
+        );
+        assert_eq!(
+            serde_json::to_string(&version("1.2.3")).unwrap(),
+            "\"1.2.3\""
+        );
+        let version: AddonVersion = serde_json::from_str("\"1.2.3-beta\"").unwrap();
+        assert_eq!(version.to_string(), "1.2.3-beta");
+    }
+
+    #[test]
+    fn bare_requirement_means_at_least() {
+        for req in [
+            req("1.2.3"),
+            req("[1,2,3]"),
+            serde_json::from_str("\"1.2.3\"").unwrap(),
+            serde_json::from_str("[1, 2, 3]").unwrap(),
+        ] {
+            assert!(req.matches(&version("1.2.3")));
+            assert!(req.matches(&version("[2,0,0]")));
+            assert!(!req.matches(&version("1.2.2")));
+        }
+        let caret = req("^1.2.3");
+        assert!(caret.matches(&version("1.9.0")));
+        assert!(!caret.matches(&version("2.0.0")));
+    }
+
+    #[test]
+    fn prerelease_requirement() {
+        let req = req("^1.9.0-beta");
+        assert!(req.matches(&version("1.9.0-beta")));
+        assert!(req.matches(&version("1.9.0-beta.2")));
+        assert!(req.matches(&version("[1,9,0]")));
+        assert!(req.matches(&version("1.10.0")));
+        assert!(!req.matches(&version("1.10.0-beta")));
+        assert!(!req.matches(&version("1.8.0")));
+        assert!(!AddonVersionReq::at_least(&version("1.0.0")).matches(&version("1.1.0-beta")));
+    }
+
+    #[test]
+    fn bump() {
+        let current = version("[1,2,3]");
+        assert_eq!(Bump::Major.apply(&current), Version::new(2, 0, 0));
+        assert_eq!(Bump::Minor.apply(&current), Version::new(1, 3, 0));
+        assert_eq!(Bump::Patch.apply(&current), Version::new(1, 2, 4));
+        assert_eq!(
+            Bump::Patch.apply(&version("1.2.3-beta")),
+            Version::new(1, 2, 4)
+        );
+        assert_eq!("minor".parse::<Bump>().unwrap(), Bump::Minor);
+        let to = "[2,0,0]".parse::<Bump>().unwrap();
+        assert_eq!(to, Bump::To(Version::new(2, 0, 0)));
+        assert_eq!(to.apply(&current), Version::new(2, 0, 0));
+    }
+
+    #[test]
+    fn with_form_of() {
+        let next = Version::new(1, 2, 4);
+        assert!(matches!(
+            version("[1,2,3]").with_form_of(next.clone()),
+            Ok(AddonVersion::Arr([1, 2, 4]))
+        ));
+        assert!(matches!(
+            version("1.2.3").with_form_of(next),
+            Ok(AddonVersion::Str(_))
+        ));
+        assert!(version("[1,2,3]")
+            .with_form_of(Version::parse("1.2.4-beta").unwrap())
+            .is_err());
+    }
+}
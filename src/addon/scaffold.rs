@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, HashMap};
+
+use semver::Version;
+use serde_json::Map;
+
+use super::{
+    manifest::{
+        format::FormatVersion, Authors, Dependency, Header, Manifest, Metadata, Module, ModuleType,
+    },
+    AddonVersion,
+};
+
+/// The script module the script packs depend on.
+pub const SERVER_MODULE: &str = "@minecraft/server";
+/// The file run by the script module of a new script pack.
+pub const SCRIPT_ENTRY: &str = "scripts/main.js";
+
+pub fn new_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// What a new pack starts with.
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    pub version: [u64; 3],
+    pub min_engine_version: [u64; 3],
+    pub authors: Vec<String>,
+}
+
+impl Template {
+    fn manifest(&self, modules: Vec<Module>) -> Manifest {
+        Manifest {
+            format_version: Some(FormatVersion::LATEST),
+            header: Header {
+                name: self.name.clone(),
+                description: Some(self.description.clone()),
+                uuid: new_uuid(),
+                version: AddonVersion::Arr(self.version),
+                min_engine_version: Some(AddonVersion::Arr(self.min_engine_version)),
+                base_game_version: None,
+                lock_template_options: None,
+                allow_random_seed: None,
+                platform_locked: None,
+                pack_scope: None,
+                extra: Map::new(),
+            },
+            modules,
+            dependencies: None,
+            capabilities: None,
+            metadata: Some(Metadata {
+                authors: (!self.authors.is_empty()).then(|| Authors::Many(self.authors.clone())),
+                license: None,
+                url: None,
+                generated_with: Some(BTreeMap::from([(
+                    env!("CARGO_PKG_NAME").to_string(),
+                    vec![env!("CARGO_PKG_VERSION").to_string()],
+                )])),
+                product_type: None,
+                extra: Map::new(),
+            }),
+            settings: None,
+            subpacks: None,
+            extra: Map::new(),
+        }
+    }
+
+    fn module(&self, module_type: ModuleType) -> Module {
+        Module {
+            module_type,
+            uuid: Some(new_uuid()),
+            version: Some(AddonVersion::Arr(self.version)),
+            description: None,
+            entry: None,
+            language: None,
+            extra: Map::new(),
+        }
+    }
+
+    pub fn behavior_pack(&self) -> Manifest {
+        self.manifest(vec![self.module(ModuleType::Data)])
+    }
+
+    pub fn resource_pack(&self) -> Manifest {
+        self.manifest(vec![self.module(ModuleType::Resources)])
+    }
+
+    /// A behavior pack running [`SCRIPT_ENTRY`] with the given version of [`SERVER_MODULE`].
+    pub fn script_pack(&self, server_version: Version) -> Manifest {
+        let mut manifest = self.behavior_pack();
+        manifest.modules.push(Module {
+            entry: Some(SCRIPT_ENTRY.to_string()),
+            language: Some("javascript".to_string()),
+            ..self.module(ModuleType::Script)
+        });
+        manifest.dependencies = Some(vec![Dependency {
+            uuid: None,
+            module_name: Some(SERVER_MODULE.to_string()),
+            // format_version 3 wants the script modules as semver strings
+            version: AddonVersion::from(server_version),
+            extra: Map::new(),
+        }]);
+        manifest
+    }
+}
+
+/// Make `pack` depend on the current version of `other`.
+pub fn link(pack: &mut Manifest, other: &Manifest) {
+    pack.dependencies
+        .get_or_insert_with(Vec::new)
+        .push(Dependency {
+            uuid: Some(other.header.uuid.clone()),
+            module_name: None,
+            version: other.header.version.clone(),
+            extra: Map::new(),
+        });
+}
+
+/// The key of the metadata recording the pack a fork was made from.
+pub const FORKED_FROM: &str = "forked_from";
+
+/// Give the packs new header and module uuids, point the dependencies between them to the new
+/// uuids, and record the uuid and version each pack was forked from in its metadata.
+pub fn fork(packs: &mut [&mut Manifest]) -> Result<(), serde_json::Error> {
+    let uuids = packs
+        .iter()
+        .map(|i| (i.header.uuid.clone(), new_uuid()))
+        .collect::<HashMap<_, _>>();
+    for pack in packs.iter_mut() {
+        let origin = serde_json::json!({
+            "uuid": pack.header.uuid,
+            "version": serde_json::to_value(&pack.header.version)?,
+        });
+        pack.metadata
+            .get_or_insert_with(Metadata::default)
+            .extra
+            .insert(FORKED_FROM.to_string(), origin);
+        pack.header.uuid = uuids[&pack.header.uuid].clone();
+        for module in pack.modules.iter_mut().filter(|i| i.uuid.is_some()) {
+            module.uuid = Some(new_uuid());
+        }
+        for dependency in pack.dependencies.iter_mut().flatten() {
+            if let Some(uuid) = dependency.uuid.as_ref().and_then(|i| uuids.get(i)) {
+                dependency.uuid = Some(uuid.clone());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,340 @@
+use super::{
+    lang::{strip_formatting, Lang},
+    manifest::{Manifest, PackateType},
+    world_packet_list::{parse_in_use_packet_list, InUse},
+    AddonVersion,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A pack folder found under `behavior_packs` or `resource_packs`.
+#[derive(Clone, Debug)]
+pub struct ScannedPack {
+    pub manifest: Manifest,
+    /// The name to show, without formatting codes and resolved from the pack's
+    /// `.lang` files after [`Scan::localize`].
+    pub name: String,
+    pub description: Option<String>,
+    pub packate_type: PackateType,
+    pub folder: PathBuf,
+    /// The entry of the pack in the pack list, `None` when the pack is not in use.
+    pub in_use: Option<InUse>,
+    /// Position of the entry in the pack list.
+    pub priority: Option<usize>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ScannedPack {
+    pub fn is_enabled(&self) -> bool {
+        self.in_use.is_some()
+    }
+
+    /// Resolve the name and the description when they are keys of the pack's `.lang` file.
+    pub fn localize(&mut self, lang: &str) {
+        if let Some(lang) = Lang::load(&self.folder, lang) {
+            let header = &self.manifest.header;
+            self.name = strip_formatting(lang.resolve(&header.name));
+            self.description = header
+                .description
+                .as_ref()
+                .map(|i| strip_formatting(lang.resolve(i)));
+        }
+    }
+
+    pub fn folder_name(&self) -> String {
+        self.folder
+            .file_name()
+            .map(|i| i.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Problems with a pack that did not stop it from being read.
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum Diagnostic {
+    #[error("the manifest does not describe a {}", .0.get_short_name())]
+    WrongFolder(PackateType),
+    #[error("version {0} of this pack is in use instead")]
+    OtherVersionInUse(AddonVersion),
+    #[error("in use {0} times")]
+    DuplicateInUse(usize),
+    #[error("the same pack is also installed in {}", .0.display())]
+    DuplicateFolder(PathBuf),
+}
+
+/// Errors that stopped a single pack or pack list from being read.
+#[derive(thiserror::Error, Debug)]
+pub enum ScanError {
+    #[error("failed to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("failed to parse {}: {source}", path.display())]
+    Manifest {
+        path: PathBuf,
+        source: serde_jsonc::Error,
+    },
+    #[error("failed to parse {}: {source}", path.display())]
+    PackList {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+/// An entry of a pack list without an installed folder.
+pub struct MissingPack {
+    pub packate_type: PackateType,
+    pub in_use: InUse,
+    pub priority: usize,
+}
+
+#[derive(Default)]
+pub struct Scan {
+    pub packs: Vec<ScannedPack>,
+    pub missing: Vec<MissingPack>,
+    pub errors: Vec<ScanError>,
+}
+
+impl Scan {
+    pub fn extend(&mut self, other: Scan) {
+        self.packs.extend(other.packs);
+        self.missing.extend(other.missing);
+        self.errors.extend(other.errors);
+    }
+
+    pub fn localize(&mut self, lang: &str) {
+        self.packs.iter_mut().for_each(|i| i.localize(lang));
+    }
+
+    pub fn enabled(&self) -> impl Iterator<Item = &ScannedPack> {
+        self.packs.iter().filter(|i| i.is_enabled())
+    }
+
+    pub fn into_enabled(self) -> Vec<ScannedPack> {
+        self.packs.into_iter().filter(|i| i.is_enabled()).collect()
+    }
+}
+
+fn read_list(path: &Path) -> Result<Vec<InUse>, ScanError> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let str = fs::read_to_string(path).map_err(|source| ScanError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_in_use_packet_list(str).map_err(|source| ScanError::PackList {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn read_manifest(folder: &Path) -> Result<Manifest, ScanError> {
+    let path = folder.join("manifest.json");
+    let str = fs::read_to_string(&path).map_err(|source| ScanError::Io {
+        path: path.clone(),
+        source,
+    })?;
+    Manifest::new(str).map_err(|source| ScanError::Manifest { path, source })
+}
+
+fn scan_dir(dir: &Path, packate_type: PackateType, list: &[InUse], scan: &mut Scan) {
+    let mut folders = match dir.read_dir() {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|i| i.file_type().is_ok_and(|i| i.is_dir()))
+            .map(|i| i.path())
+            .collect::<Vec<_>>(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(source) => {
+            scan.errors.push(ScanError::Io {
+                path: dir.to_path_buf(),
+                source,
+            });
+            Vec::new()
+        }
+    };
+    folders.sort();
+
+    let first = scan.packs.len();
+    for folder in folders {
+        let manifest = match read_manifest(&folder) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                scan.errors.push(err);
+                continue;
+            }
+        };
+        let mut diagnostics = Vec::new();
+        if !PackateType::try_from(&manifest).is_ok_and(|i| i == packate_type) {
+            diagnostics.push(Diagnostic::WrongFolder(packate_type));
+        }
+        let header = &manifest.header;
+        let matches = list
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.pack_id == header.uuid)
+            .collect::<Vec<_>>();
+        let in_use = matches.iter().find(|(_, i)| i.version == header.version);
+        if in_use.is_none() {
+            if let Some((_, other)) = matches.first() {
+                diagnostics.push(Diagnostic::OtherVersionInUse(other.version.clone()));
+            }
+        }
+        let count = matches
+            .iter()
+            .filter(|(_, i)| i.version == header.version)
+            .count();
+        if count > 1 {
+            diagnostics.push(Diagnostic::DuplicateInUse(count));
+        }
+        if let Some(other) = scan.packs[first..].iter_mut().find(|i| {
+            i.manifest.header.uuid == header.uuid && i.manifest.header.version == header.version
+        }) {
+            other
+                .diagnostics
+                .push(Diagnostic::DuplicateFolder(folder.clone()));
+            diagnostics.push(Diagnostic::DuplicateFolder(other.folder.clone()));
+        }
+        scan.packs.push(ScannedPack {
+            name: strip_formatting(&header.name),
+            description: header.description.as_ref().map(strip_formatting),
+            in_use: in_use.map(|(_, i)| (*i).clone()),
+            priority: in_use.map(|(index, _)| *index),
+            manifest,
+            packate_type,
+            folder,
+            diagnostics,
+        });
+    }
+
+    let installed = &scan.packs[first..];
+    for (priority, in_use) in list.iter().enumerate() {
+        if !installed.iter().any(|i| {
+            i.manifest.header.uuid == in_use.pack_id && i.manifest.header.version == in_use.version
+        }) {
+            scan.missing.push(MissingPack {
+                packate_type,
+                in_use: in_use.clone(),
+                priority,
+            });
+        }
+    }
+}
+
+/// Scan the packs of one type installed in a world, along with its pack list.
+pub fn scan_world_packs<P: AsRef<Path>>(world: P, packate_type: PackateType) -> Scan {
+    let world = world.as_ref();
+    let mut scan = Scan::default();
+    let list = match read_list(&world.join(packate_type.get_list_file_string())) {
+        Ok(list) => list,
+        Err(err) => {
+            scan.errors.push(err);
+            Vec::new()
+        }
+    };
+    scan_dir(
+        &world.join(packate_type.get_path_name()),
+        packate_type,
+        &list,
+        &mut scan,
+    );
+    scan
+}
+
+/// Scan the resource and behavior packs installed in a world.
+pub fn scan_world<P: AsRef<Path>>(world: P) -> Scan {
+    let mut scan = scan_world_packs(&world, PackateType::Resource);
+    scan.extend(scan_world_packs(&world, PackateType::Behavior));
+    scan
+}
+
+/// Scan the packs installed at the server level of a bds, which are not in use by themselves.
+pub fn scan_server<P: AsRef<Path>>(bds_root: P) -> Scan {
+    let mut scan = Scan::default();
+    for packate_type in [PackateType::Resource, PackateType::Behavior] {
+        scan_dir(
+            &bds_root.as_ref().join(packate_type.get_path_name()),
+            packate_type,
+            &[],
+            &mut scan,
+        );
+    }
+    scan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(path: &Path, str: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, str).unwrap();
+    }
+
+    fn manifest(uuid: &str) -> String {
+        format!(
+            r#"{{
+                "format_version": 2,
+                "header": {{"name": "§aGood", "uuid": "{uuid}", "version": [1, 0, 0]}},
+                "modules": [{{"type": "data", "uuid": "m-{uuid}", "version": [1, 0, 0]}}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn broken_pack_does_not_stop_the_scan() {
+        let world = tempdir().unwrap();
+        let world = world.path();
+        write(
+            &world.join("behavior_packs/broken/manifest.json"),
+            "{\"header\": ",
+        );
+        write(
+            &world.join("behavior_packs/good/manifest.json"),
+            &manifest("g"),
+        );
+        write(
+            &world.join("world_behavior_packs.json"),
+            r#"[{"pack_id": "g", "version": [1, 0, 0]}, {"pack_id": "x", "version": [1, 0, 0]}]"#,
+        );
+
+        let scan = scan_world(world);
+        assert_eq!(scan.packs.len(), 1);
+        let pack = &scan.packs[0];
+        assert_eq!(pack.name, "Good");
+        assert_eq!(pack.folder_name(), "good");
+        assert!(pack.is_enabled());
+        assert_eq!(pack.priority, Some(0));
+        assert!(pack.diagnostics.is_empty());
+        assert!(matches!(
+            &scan.errors[..],
+            [ScanError::Manifest { path, .. }] if path.ends_with("broken/manifest.json")
+        ));
+        assert!(matches!(
+            &scan.missing[..],
+            [MissingPack { in_use, priority: 1, .. }] if in_use.pack_id == "x"
+        ));
+    }
+
+    #[test]
+    fn broken_pack_list_keeps_the_packs() {
+        let world = tempdir().unwrap();
+        let world = world.path();
+        write(
+            &world.join("resource_packs/good/manifest.json"),
+            &manifest("g"),
+        );
+        write(&world.join("world_resource_packs.json"), "[{");
+
+        let scan = scan_world_packs(world, PackateType::Resource);
+        assert_eq!(scan.packs.len(), 1);
+        assert!(!scan.packs[0].is_enabled());
+        assert!(matches!(
+            scan.packs[0].diagnostics[..],
+            [Diagnostic::WrongFolder(PackateType::Resource)]
+        ));
+        assert!(matches!(&scan.errors[..], [ScanError::PackList { .. }]));
+    }
+}
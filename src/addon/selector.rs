@@ -0,0 +1,268 @@
+use std::fmt::Display;
+
+use semver::Version;
+
+use super::{scanner::ScannedPack, AddonVersion};
+
+/// How close a name has to be to the query to be suggested, from 0 to 1.
+const SUGGESTION_THRESHOLD: f64 = 0.7;
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A way to refer to installed packs on the command line: a uuid or a unique uuid prefix, a name
+/// or a folder name (both ignoring case), optionally followed by `@version`.
+#[derive(Clone, Debug)]
+pub struct Selector {
+    pub query: String,
+    pub version: Option<Version>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SelectError {
+    #[error("No matching packages found for {query}{}", did_you_mean(.suggestions))]
+    NoMatch {
+        query: String,
+        suggestions: Vec<String>,
+    },
+    #[error("The uuid prefix {prefix} is ambiguous, it matches {}", .uuids.join(", "))]
+    AmbiguousPrefix { prefix: String, uuids: Vec<String> },
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [one] => format!(", did you mean {one}?"),
+        many => format!(", did you mean one of {}?", many.join(", ")),
+    }
+}
+
+impl Selector {
+    /// Split off an `@version` suffix, the text is kept as a whole when the suffix is not a version.
+    pub fn parse<S: AsRef<str>>(str: S) -> Selector {
+        let str = str.as_ref();
+        if let Some((query, version)) = str.rsplit_once('@') {
+            if let Ok(version) = version.parse::<AddonVersion>() {
+                return Selector {
+                    query: query.to_string(),
+                    version: Some(Version::from(&version)),
+                };
+            }
+        }
+        Selector {
+            query: str.to_string(),
+            version: None,
+        }
+    }
+
+    /// Indices of the packs the selector refers to. The first of these that matches anything wins:
+    /// the exact uuid, the name, the name ignoring case, the folder name ignoring case and a uuid prefix.
+    pub fn select(&self, packs: &[ScannedPack]) -> Result<Vec<usize>, SelectError> {
+        let query = self.query.as_str();
+        let lower = query.to_lowercase();
+        let tiers: [&dyn Fn(&ScannedPack) -> bool; 4] = [
+            &|i| i.manifest.header.uuid.eq_ignore_ascii_case(query),
+            &|i| i.name == query,
+            &|i| i.name.to_lowercase() == lower,
+            &|i| i.folder_name().to_lowercase() == lower,
+        ];
+        let mut res = tiers
+            .iter()
+            .map(|tier| {
+                (0..packs.len())
+                    .filter(|i| tier(&packs[*i]))
+                    .collect::<Vec<_>>()
+            })
+            .find(|i| !i.is_empty())
+            .unwrap_or_default();
+        if res.is_empty() && !lower.is_empty() {
+            res = (0..packs.len())
+                .filter(|i| {
+                    packs[*i]
+                        .manifest
+                        .header
+                        .uuid
+                        .to_lowercase()
+                        .starts_with(&lower)
+                })
+                .collect();
+            let mut uuids = res
+                .iter()
+                .map(|i| packs[*i].manifest.header.uuid.clone())
+                .collect::<Vec<_>>();
+            uuids.sort();
+            uuids.dedup();
+            if uuids.len() > 1 {
+                return Err(SelectError::AmbiguousPrefix {
+                    prefix: query.to_string(),
+                    uuids,
+                });
+            }
+        }
+        if res.is_empty() {
+            return Err(SelectError::NoMatch {
+                query: self.to_string(),
+                suggestions: suggest(query, packs),
+            });
+        }
+
+        if let Some(version) = &self.version {
+            let all = res.clone();
+            res.retain(|i| Version::from(&packs[*i].manifest.header.version) == *version);
+            if res.is_empty() {
+                return Err(SelectError::NoMatch {
+                    query: self.to_string(),
+                    suggestions: all
+                        .iter()
+                        .map(|i| format!("{query}@{}", packs[*i].manifest.header.version))
+                        .collect(),
+                });
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}@{}", self.query, version),
+            None => write!(f, "{}", self.query),
+        }
+    }
+}
+
+/// The names and folder names that look like the query, the closest first.
+fn suggest(query: &str, packs: &[ScannedPack]) -> Vec<String> {
+    let query = query.to_lowercase();
+    let mut scored = packs
+        .iter()
+        .flat_map(|i| [i.name.clone(), i.folder_name()])
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let lower = name.to_lowercase();
+            let score = if lower.contains(&query) || query.contains(&lower) {
+                1.0
+            } else {
+                strsim::jaro_winkler(&query, &lower)
+            };
+            (score, name)
+        })
+        .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+        .collect::<Vec<_>>();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let mut out: Vec<String> = Vec::new();
+    for (_, name) in scored {
+        if !out.contains(&name) {
+            out.push(name);
+        }
+    }
+    out.truncate(MAX_SUGGESTIONS);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::addon::manifest::{Manifest, PackateType};
+
+    fn pack(name: &str, uuid: &str, version: &str, folder: &str) -> ScannedPack {
+        let manifest = Manifest::new(format!(
+            r#"{{
+                "header": {{"name": "{name}", "uuid": "{uuid}", "version": "{version}"}},
+                "modules": [{{"type": "data", "uuid": "m", "version": [1, 0, 0]}}]
+            }}"#
+        ))
+        .unwrap();
+        ScannedPack {
+            manifest,
+            name: name.to_string(),
+            description: None,
+            packate_type: PackateType::Behavior,
+            folder: format!("behavior_packs/{folder}").into(),
+            in_use: None,
+            priority: None,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn packs() -> Vec<ScannedPack> {
+        vec![
+            pack("Alpha", "11111111-aaaa", "1.0.0", "beta"),
+            pack("alpha", "22222222-aaaa", "1.0.0", "gamma"),
+            pack("Gamma", "33333333-aaaa", "1.0.0", "alpha_bp"),
+            pack("Beta", "44444444-aaaa", "1.0.0", "other"),
+            pack("Twin", "abcd0000-0001", "1.0.0", "twin_a"),
+            pack("Twin", "abcd0000-0002", "1.0.0", "twin_b"),
+            pack("Versioned", "55555555-aaaa", "1.0.0", "v1"),
+            pack("Versioned", "55555555-aaaa", "2.0.0", "v2"),
+        ]
+    }
+
+    fn select(str: &str) -> Result<Vec<usize>, SelectError> {
+        Selector::parse(str).select(&packs())
+    }
+
+    #[test]
+    fn parse() {
+        let selector = Selector::parse("Pack@1.2.3");
+        assert_eq!(selector.query, "Pack");
+        assert_eq!(selector.version, Some(Version::new(1, 2, 3)));
+        let selector = Selector::parse("Pack@[1,2,3]");
+        assert_eq!(selector.query, "Pack");
+        assert_eq!(selector.version, Some(Version::new(1, 2, 3)));
+        assert_eq!(selector.to_string(), "Pack@1.2.3");
+        let selector = Selector::parse("me@home");
+        assert_eq!(selector.query, "me@home");
+        assert_eq!(selector.version, None);
+    }
+
+    #[test]
+    fn tiers() {
+        // the uuid ignoring case
+        assert_eq!(select("11111111-AAAA").unwrap(), [0]);
+        // the exact name before the name ignoring case
+        assert_eq!(select("Alpha").unwrap(), [0]);
+        assert_eq!(select("ALPHA").unwrap(), [0, 1]);
+        // the name before the folder name
+        assert_eq!(select("beta").unwrap(), [3]);
+        assert_eq!(select("Alpha_BP").unwrap(), [2]);
+        // a uuid prefix last
+        assert_eq!(select("2222").unwrap(), [1]);
+    }
+
+    #[test]
+    fn uuid_prefix() {
+        assert!(matches!(
+            select("abcd"),
+            Err(SelectError::AmbiguousPrefix { uuids, .. })
+                if uuids == ["abcd0000-0001", "abcd0000-0002"]
+        ));
+        // several versions of one pack are not ambiguous
+        assert_eq!(select("5555").unwrap(), [6, 7]);
+        assert_eq!(select("abcd0000-0002").unwrap(), [5]);
+    }
+
+    #[test]
+    fn version() {
+        assert_eq!(select("Versioned@2.0.0").unwrap(), [7]);
+        assert_eq!(select("5555@[1,0,0]").unwrap(), [6]);
+        assert!(matches!(
+            select("Versioned@3.0.0"),
+            Err(SelectError::NoMatch { query, suggestions })
+                if query == "Versioned@3.0.0"
+                    && suggestions == ["Versioned@1.0.0", "Versioned@2.0.0"]
+        ));
+    }
+
+    #[test]
+    fn suggestions() {
+        let Err(SelectError::NoMatch { suggestions, .. }) = select("Gamam") else {
+            panic!("Gamam should not match");
+        };
+        assert!(suggestions.contains(&"Gamma".to_string()));
+        assert!(suggestions.len() <= MAX_SUGGESTIONS);
+        assert!(matches!(
+            select("zzzzzz"),
+            Err(SelectError::NoMatch { suggestions, .. }) if suggestions.is_empty()
+        ));
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{
+    json_style::JsonStyle,
+    manifest::PackateType,
+    scanner::ScannedPack,
+    world_packet_list::{packet_list_style, read_history, History},
+    AddonVersion,
+};
+
+/// The folder in a world where the removed packs are kept until the trash is emptied.
+pub const TRASH_DIR: &str = ".addon_trash";
+const ENTRY_FILE: &str = "entry.json";
+/// The id the next removed pack gets, kept so that ids are not reused after the trash is emptied.
+const NEXT_ID_FILE: &str = "next_id";
+
+/// What was removed and where it was, stored next to the removed pack folder.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrashEntry {
+    pub id: u64,
+    /// The name to show, see [`ScannedPack::name`].
+    pub name: String,
+    /// The name in the manifest, which may be a key of the pack's `.lang` files. Entries written
+    /// by older versions do not have it.
+    #[serde(default)]
+    pub header_name: Option<String>,
+    pub uuid: String,
+    pub version: AddonVersion,
+    pub packate_type: PackateType,
+    /// Name of the pack folder in `behavior_packs` or `resource_packs`.
+    pub folder: String,
+    /// The entry of the pack in the world pack list, as it was written.
+    pub in_use: Option<Map<String, Value>>,
+    /// Position of the pack in the world pack list.
+    pub priority: Option<usize>,
+    /// The style of the world pack list before the removal, for restoring the entry into a list
+    /// that has been emptied since.
+    #[serde(default)]
+    pub list_style: Option<JsonStyle>,
+    /// The records of the pack in the world history, put back by a restore if they are gone.
+    #[serde(default)]
+    pub history: Vec<History>,
+    /// Unix time in seconds.
+    pub removed_at: u64,
+}
+
+impl TrashEntry {
+    pub fn dir<P: AsRef<Path>>(&self, world: P) -> PathBuf {
+        world.as_ref().join(TRASH_DIR).join(self.id.to_string())
+    }
+
+    /// Where the pack folder is kept in the trash.
+    pub fn pack_path<P: AsRef<Path>>(&self, world: P) -> PathBuf {
+        self.dir(world).join(&self.folder)
+    }
+
+    /// Seconds since the pack was removed.
+    pub fn age(&self) -> u64 {
+        now().saturating_sub(self.removed_at)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|i| i.as_secs())
+        .unwrap_or_default()
+}
+
+/// All entries of the trash of a world, oldest first. Folders without a readable entry are skipped.
+pub fn list<P: AsRef<Path>>(world: P) -> Result<Vec<TrashEntry>> {
+    let dir = world.as_ref().join(TRASH_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+        .filter_map(|i| fs::read_to_string(i.path().join(ENTRY_FILE)).ok())
+        .filter_map(|i| serde_json::from_str::<TrashEntry>(&i).ok())
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|i| i.id);
+    Ok(entries)
+}
+
+pub fn get<P: AsRef<Path>>(world: P, id: u64) -> Result<TrashEntry> {
+    list(world)?
+        .into_iter()
+        .find(|i| i.id == id)
+        .ok_or_else(|| anyhow!("There is no entry {id} in the trash"))
+}
+
+/// Take an id for a new entry, larger than any id the trash has given before.
+fn next_id(world: &Path) -> Result<u64> {
+    let path = world.join(TRASH_DIR).join(NEXT_ID_FILE);
+    let stored = fs::read_to_string(&path)
+        .ok()
+        .and_then(|i| i.trim().parse::<u64>().ok())
+        .unwrap_or(1);
+    let id = stored.max(list(world)?.last().map(|i| i.id + 1).unwrap_or(1));
+    fs::create_dir_all(world.join(TRASH_DIR))
+        .with_context(|| format!("Failed to create {}", world.join(TRASH_DIR).display()))?;
+    fs::write(&path, (id + 1).to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(id)
+}
+
+/// Move a pack folder into the trash of the world, `priority` is its position in the world pack list.
+pub fn put<P: AsRef<Path>>(
+    world: P,
+    pack: &ScannedPack,
+    priority: Option<usize>,
+) -> Result<TrashEntry> {
+    let world = world.as_ref();
+    let id = next_id(world)?;
+    let list_file = world.join(pack.packate_type.get_list_file_string());
+    let in_use = pack.in_use.as_ref().map(|in_use| {
+        // the entry as written, falling back to the one that was read
+        fs::read_to_string(&list_file)
+            .ok()
+            .and_then(|i| serde_json::from_str::<Vec<Map<String, Value>>>(&i).ok())
+            .and_then(|list| list.into_iter().nth(priority?))
+            .filter(|i| i.get("pack_id") == Some(&Value::from(in_use.pack_id.as_str())))
+            .or_else(|| match serde_json::to_value(in_use) {
+                Ok(Value::Object(map)) => Some(map),
+                _ => None,
+            })
+            .unwrap_or_default()
+    });
+    let entry = TrashEntry {
+        id,
+        name: pack.name.clone(),
+        header_name: Some(pack.manifest.header.name.clone()),
+        uuid: pack.manifest.header.uuid.clone(),
+        version: pack.manifest.header.version.clone(),
+        packate_type: pack.packate_type,
+        folder: pack.folder_name(),
+        list_style: in_use
+            .is_some()
+            .then(|| packet_list_style(world, pack.packate_type)),
+        in_use,
+        priority,
+        history: read_history(world, pack.packate_type)
+            .map(|i| {
+                i.packs
+                    .into_iter()
+                    .filter(|i| {
+                        i.uuid == pack.manifest.header.uuid
+                            && i.version == pack.manifest.header.version
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        removed_at: now(),
+    };
+    let dir = entry.dir(world);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    // written first, so that a pack in the trash always has an entry
+    let entry_path = dir.join(ENTRY_FILE);
+    if let Err(err) = fs::write(&entry_path, serde_json::to_string_pretty(&entry)?) {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(err).with_context(|| format!("Failed to write {}", entry_path.display()));
+    }
+    if let Err(err) = fs::rename(&pack.folder, entry.pack_path(world)) {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(err).with_context(|| {
+            format!(
+                "Failed to move {} to {}",
+                pack.folder.display(),
+                dir.display()
+            )
+        });
+    }
+    Ok(entry)
+}
+
+/// Permanently delete an entry and its pack folder.
+pub fn delete<P: AsRef<Path>>(world: P, entry: &TrashEntry) -> Result<()> {
+    let dir = entry.dir(world);
+    fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))
+}
@@ -1,12 +1,43 @@
-use std::hash::Hash;
+use std::{fs, hash::Hash, path::Path};
 
-use super::AddonVersion;
+use super::{json_style::JsonStyle, manifest::PackateType, AddonVersion};
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Hash, Clone)]
+/// An entry of a world pack list, two entries are equal when they refer to the same pack version.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InUse {
     pub pack_id: String,
     pub version: AddonVersion,
+    /// The other fields of the entry, such as `subpack`.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl InUse {
+    pub fn new(pack_id: String, version: AddonVersion) -> InUse {
+        InUse {
+            pack_id,
+            version,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl PartialEq for InUse {
+    fn eq(&self, other: &Self) -> bool {
+        self.pack_id == other.pack_id && self.version == other.version
+    }
+}
+
+impl Eq for InUse {}
+
+impl Hash for InUse {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.pack_id.hash(state);
+        self.version.hash(state);
+    }
 }
 
 pub fn parse_in_use_packet_list<S: AsRef<str>>(str: S) -> Result<Vec<InUse>, serde_json::Error> {
@@ -17,26 +48,241 @@ pub fn to_packet_list_string<V: AsRef<Vec<InUse>>>(list: V) -> Result<String, se
     serde_json::to_string(list.as_ref())
 }
 
-#[derive(Serialize, Deserialize)]
+/// A pack the world has used, kept by the game even after the pack is gone.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct History {
     pub can_be_redownloaded: bool,
     pub name: String,
-    pub uuin: String,
+    pub uuid: String,
     pub version: AddonVersion,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct HistoryList {
     pub packs: Vec<History>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl HistoryList {
+    /// Remember that the world uses the pack, replacing the version recorded for its uuid.
+    pub fn record(&mut self, name: &str, uuid: &str, version: &AddonVersion) {
+        match self.packs.iter_mut().find(|i| i.uuid == uuid) {
+            Some(history) => {
+                history.name = name.to_string();
+                history.version = version.clone();
+            }
+            None => self.packs.push(History {
+                can_be_redownloaded: false,
+                name: name.to_string(),
+                uuid: uuid.to_string(),
+                version: version.clone(),
+                extra: Map::new(),
+            }),
+        }
+    }
+
+    /// Forget the pack, returning whether it was recorded.
+    pub fn forget(&mut self, uuid: &str, version: &AddonVersion) -> bool {
+        let len = self.packs.len();
+        self.packs
+            .retain(|i| !(i.uuid == uuid && &i.version == version));
+        len != self.packs.len()
+    }
+
+    /// Add back records taken from the list before, returning whether any of them was missing.
+    pub fn restore(&mut self, records: &[History]) -> bool {
+        let len = self.packs.len();
+        for record in records {
+            if !self
+                .packs
+                .iter()
+                .any(|i| i.uuid == record.uuid && i.version == record.version)
+            {
+                self.packs.push(record.clone());
+            }
+        }
+        len != self.packs.len()
+    }
 }
 
 pub fn pares_packs_history_list<S: AsRef<str>>(str: S) -> Result<HistoryList, serde_json::Error> {
     serde_json::from_str(str.as_ref())
 }
 
-pub fn to_packs_history_list_string<V: AsRef<HistoryList>>(
-    val: V,
-) -> Result<String, serde_json::Error> {
-    serde_json::to_string(val.as_ref())
+pub fn to_packs_history_list_string(val: &HistoryList) -> Result<String, serde_json::Error> {
+    serde_json::to_string(val)
+}
+
+pub fn read_packet_list<P: AsRef<Path>>(
+    target: P,
+    packate_type: PackateType,
+) -> anyhow::Result<Vec<InUse>> {
+    let path = target.as_ref().join(packate_type.get_list_file_string());
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    parse_in_use_packet_list(
+        fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", packate_type.get_list_file_string()))?,
+    )
+    .with_context(|| {
+        format!(
+            "Failed to properly parse an existing {}",
+            packate_type.get_list_file_string()
+        )
+    })
+}
+
+/// The formatting and the key order of the existing pack list, compact when there is none.
+pub fn packet_list_style<P: AsRef<Path>>(target: P, packate_type: PackateType) -> JsonStyle {
+    fs::read_to_string(target.as_ref().join(packate_type.get_list_file_string()))
+        .map(JsonStyle::of)
+        .unwrap_or_else(|_| JsonStyle::compact())
+}
+
+pub fn write_packet_list<P: AsRef<Path>, V: AsRef<Vec<InUse>>>(
+    target: P,
+    packate_type: PackateType,
+    list: V,
+) -> anyhow::Result<()> {
+    let style = packet_list_style(&target, packate_type);
+    write_packet_list_in_style(target, packate_type, list, &style)
 }
 
+pub fn write_packet_list_in_style<P: AsRef<Path>, V: AsRef<Vec<InUse>>>(
+    target: P,
+    packate_type: PackateType,
+    list: V,
+    style: &JsonStyle,
+) -> anyhow::Result<()> {
+    let path = target.as_ref().join(packate_type.get_list_file_string());
+    fs::write(&path, style.to_string(list.as_ref())?)
+        .with_context(|| format!("Failed to write {}", packate_type.get_list_file_string()))
+}
+
+pub fn read_history<P: AsRef<Path>>(
+    target: P,
+    packate_type: PackateType,
+) -> anyhow::Result<HistoryList> {
+    let path = target.as_ref().join(packate_type.get_history_file_string());
+    if !path.exists() {
+        return Ok(HistoryList::default());
+    }
+    pares_packs_history_list(
+        fs::read_to_string(path).with_context(|| {
+            format!("Failed to read {}", packate_type.get_history_file_string())
+        })?,
+    )
+    .with_context(|| {
+        format!(
+            "Failed to properly parse an existing {}",
+            packate_type.get_history_file_string()
+        )
+    })
+}
+
+pub fn write_history<P: AsRef<Path>>(
+    target: P,
+    packate_type: PackateType,
+    history: &HistoryList,
+) -> anyhow::Result<()> {
+    let path = target.as_ref().join(packate_type.get_history_file_string());
+    let style = fs::read_to_string(&path)
+        .map(JsonStyle::of)
+        .unwrap_or_else(|_| JsonStyle::compact());
+    fs::write(&path, style.to_string(history)?)
+        .with_context(|| format!("Failed to write {}", packate_type.get_history_file_string()))
+}
+
+/// Record a pack that was put in use in the history of the world.
+pub fn record_history<P: AsRef<Path>>(
+    target: P,
+    packate_type: PackateType,
+    name: &str,
+    uuid: &str,
+    version: &AddonVersion,
+) -> anyhow::Result<()> {
+    let target = target.as_ref();
+    let mut history = read_history(target, packate_type)?;
+    history.record(name, uuid, version);
+    write_history(target, packate_type, &history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "11111111-1111-4111-8111-111111111111";
+    const OTHER: &str = "22222222-2222-4222-8222-222222222222";
+
+    fn version(str: &str) -> AddonVersion {
+        str.parse().unwrap()
+    }
+
+    fn history() -> HistoryList {
+        pares_packs_history_list(format!(
+            r#"{{"packs": [
+                {{"can_be_redownloaded": true, "name": "a", "uuid": "{UUID}", "version": [1, 0, 0], "x": 1}},
+                {{"can_be_redownloaded": false, "name": "b", "uuid": "{OTHER}", "version": [2, 0, 0]}}
+            ]}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn record() {
+        let mut list = history();
+        list.record("c", UUID, &version("[1, 1, 0]"));
+        assert_eq!(list.packs.len(), 2);
+        let updated = &list.packs[0];
+        assert_eq!(
+            (updated.name.as_str(), &updated.version),
+            ("c", &version("[1, 1, 0]"))
+        );
+        assert!(updated.can_be_redownloaded);
+        assert_eq!(updated.extra["x"], 1);
+
+        let uuid = "33333333-3333-4333-8333-333333333333";
+        list.record("d", uuid, &version("[1, 0, 0]"));
+        assert_eq!(list.packs.len(), 3);
+        assert_eq!(list.packs[2].uuid, uuid);
+        assert!(!list.packs[2].can_be_redownloaded);
+    }
+
+    #[test]
+    fn forget() {
+        let mut list = history();
+        assert!(!list.forget(UUID, &version("[1, 1, 0]")));
+        assert!(!list.forget(
+            "33333333-3333-4333-8333-333333333333",
+            &version("[1, 0, 0]")
+        ));
+        assert_eq!(list.packs.len(), 2);
+        assert!(list.forget(UUID, &version("[1, 0, 0]")));
+        assert_eq!(list.packs.len(), 1);
+        assert_eq!(list.packs[0].uuid, OTHER);
+    }
+
+    #[test]
+    fn restore() {
+        let mut list = history();
+        let records = list.packs[..1].to_vec();
+        assert!(!list.restore(&records));
+        assert!(!list.restore(&[]));
+        assert_eq!(list.packs.len(), 2);
+
+        list.forget(UUID, &version("[1, 0, 0]"));
+        assert!(list.restore(&records));
+        assert_eq!(list.packs.len(), 2);
+        let restored = &list.packs[1];
+        assert_eq!(
+            (restored.uuid.as_str(), restored.name.as_str()),
+            (UUID, "a")
+        );
+        assert!(restored.can_be_redownloaded);
+        assert_eq!(restored.extra["x"], 1);
+    }
+}
@@ -1,3 +1,123 @@
+pub mod bump;
+pub mod deps;
+pub mod fork;
+pub mod history;
+pub mod info;
 pub mod install;
+pub mod lint;
 pub mod list;
+pub mod new;
+pub mod prompt;
+pub mod prune;
 pub mod remove;
+pub mod show;
+pub mod stats;
+pub mod trash;
+
+use crate::addon::{json_style::JsonStyle, manifest::Manifest, scanner::Scan};
+use anyhow::{Context, Result};
+use console::style;
+use prettytable::{
+    format::{self, LineSeparator},
+    Table,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Print a table with a line under its titles.
+pub(crate) fn print_titled_table(tab: &mut Table) {
+    tab.set_format(
+        format::FormatBuilder::new()
+            .column_separator('|')
+            .padding(1, 1)
+            .separator(
+                format::LinePosition::Title,
+                LineSeparator::new('-', '+', '+', '+'),
+            )
+            .build(),
+    );
+    tab.printstd();
+}
+
+/// Print a table of names and values, followed by an empty line.
+pub(crate) fn print_kv_table(tab: &mut Table) {
+    tab.set_format(
+        format::FormatBuilder::new()
+            .column_separator('|')
+            .padding(1, 1)
+            .separator(
+                format::LinePosition::Bottom,
+                LineSeparator::new(' ', ' ', ' ', ' '),
+            )
+            .build(),
+    );
+    tab.printstd();
+}
+
+/// A pack project being edited.
+pub(crate) struct PackFolder {
+    pub folder: PathBuf,
+    /// The manifest as it was read, to write it back in the same style.
+    pub text: String,
+    pub manifest: Manifest,
+}
+
+impl PackFolder {
+    pub fn read(folder: PathBuf) -> Result<PackFolder> {
+        let path = folder.join("manifest.json");
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let manifest =
+            Manifest::new(&text).with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(PackFolder {
+            folder,
+            text,
+            manifest,
+        })
+    }
+
+    /// Write the manifest into `folder` in the style it was read with, without its comments.
+    pub fn write(&self, folder: &Path) -> Result<()> {
+        let path = folder.join("manifest.json");
+        fs::write(&path, JsonStyle::of(&self.text).to_string(&self.manifest)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// `dir` when it is a pack, or else the packs in its sub folders, such as the packs of an addon.
+pub(crate) fn find_pack_folders(dir: &Path) -> Result<Vec<PathBuf>> {
+    if dir.join("manifest.json").is_file() {
+        return Ok(vec![dir.to_path_buf()]);
+    }
+    let mut folders = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+        .map(|i| i.path())
+        .filter(|i| i.join("manifest.json").is_file())
+        .collect::<Vec<_>>();
+    folders.sort();
+    Ok(folders)
+}
+
+/// Report the packs that could not be read and the problems found while scanning.
+pub(crate) fn print_scan_warnings(scan: &Scan) {
+    let warning = style("warning:").yellow();
+    for err in &scan.errors {
+        eprintln!("{warning} {err}");
+    }
+    for pack in &scan.packs {
+        for diagnostic in &pack.diagnostics {
+            eprintln!("{warning} {}: {diagnostic}", pack.folder.display());
+        }
+    }
+    for missing in &scan.missing {
+        eprintln!(
+            "{warning} {} [{}] is in {} but not installed in the world",
+            missing.in_use.pack_id,
+            missing.in_use.version,
+            missing.packate_type.get_list_file_string()
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::{anyhow, Context, Result};
+use semver::Version;
+use std::{collections::HashMap, path::Path};
+
+use super::{find_pack_folders, PackFolder};
+use crate::addon::manifest::version::Bump;
+
+/// Set the header and module versions of the packs to their next version, each from its own
+/// current version, and the versions of the dependencies between them to match the new header
+/// versions, keeping the array or string form of every field.
+fn bump_versions(packs: &mut [PackFolder], bump: &Bump) -> Result<()> {
+    let versions = packs
+        .iter()
+        .map(|i| {
+            (
+                i.manifest.header.uuid.clone(),
+                bump.apply(&i.manifest.header.version),
+            )
+        })
+        .collect::<HashMap<String, Version>>();
+    for pack in packs {
+        let manifest = &mut pack.manifest;
+        let version = versions[&manifest.header.uuid].clone();
+        let context = || format!("Failed to bump {}", pack.folder.display());
+        println!(
+            "{}: {} -> {version}",
+            manifest.header.name, manifest.header.version
+        );
+        manifest.header.version = manifest
+            .header
+            .version
+            .with_form_of(version.clone())
+            .with_context(context)?;
+        for module in manifest.modules.iter_mut() {
+            if let Some(module_version) = &mut module.version {
+                *module_version = module_version
+                    .with_form_of(bump.apply(module_version))
+                    .with_context(context)?;
+            }
+        }
+        for dependency in manifest.dependencies.iter_mut().flatten() {
+            let Some(version) = dependency.uuid.as_ref().and_then(|i| versions.get(i)) else {
+                continue;
+            };
+            println!(
+                "  dependency on {}: {} -> {version}",
+                dependency.uuid.as_deref().unwrap_or_default(),
+                dependency.version
+            );
+            dependency.version = dependency
+                .version
+                .with_form_of(version.clone())
+                .with_context(context)?;
+        }
+    }
+    Ok(())
+}
+
+/// Bump the version of the packs in `paths`, each a pack folder or a folder of packs such as a
+/// linked behavior and resource pack pair.
+pub fn bump<P: AsRef<Path>>(bump: &Bump, paths: &[P], dry_run: bool) -> Result<()> {
+    let mut folders = Vec::new();
+    for path in paths {
+        folders.extend(find_pack_folders(path.as_ref())?);
+    }
+    folders.sort();
+    folders.dedup();
+    if folders.is_empty() {
+        return Err(anyhow!("No pack found"));
+    }
+    let mut packs = folders
+        .into_iter()
+        .map(PackFolder::read)
+        .collect::<Result<Vec<_>>>()?;
+    bump_versions(&mut packs, bump)?;
+    if dry_run {
+        println!("dry run, no manifest was changed");
+        return Ok(());
+    }
+    packs.iter().try_for_each(|i| i.write(&i.folder))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::addon::manifest::Manifest;
+
+    fn pack(text: &str) -> PackFolder {
+        PackFolder {
+            folder: PathBuf::new(),
+            text: text.to_string(),
+            manifest: Manifest::new(text).unwrap(),
+        }
+    }
+
+    fn bumped(bump: &str) -> Vec<String> {
+        let mut packs = [
+            pack(
+                r#"{
+                    "format_version": 2,
+                    "header": {"name": "bp", "uuid": "b", "version": [1, 2, 0]},
+                    "modules": [
+                        {"type": "data", "uuid": "b1", "version": [1, 2, 0]},
+                        {"type": "script", "uuid": "b2", "version": "0.3.1"}
+                    ],
+                    "dependencies": [{"uuid": "r", "version": [2, 0, 0]}]
+                }"#,
+            ),
+            pack(
+                r#"{
+                    "format_version": 2,
+                    "header": {"name": "rp", "uuid": "r", "version": [2, 0, 0]},
+                    "modules": [{"type": "resources", "uuid": "r1", "version": [1, 0, 0]}]
+                }"#,
+            ),
+        ];
+        bump_versions(&mut packs, &bump.parse().unwrap()).unwrap();
+        packs
+            .iter()
+            .flat_map(|i| {
+                let manifest = &i.manifest;
+                [manifest.header.version.to_string()]
+                    .into_iter()
+                    .chain(
+                        manifest
+                            .modules
+                            .iter()
+                            .flat_map(|i| &i.version)
+                            .map(|i| i.to_string()),
+                    )
+                    .chain(
+                        manifest
+                            .dependencies
+                            .iter()
+                            .flatten()
+                            .map(|i| i.version.to_string()),
+                    )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn modules_bump_from_their_own_version() {
+        assert_eq!(
+            bumped("minor"),
+            ["1.3.0", "1.3.0", "0.4.0", "2.1.0", "2.1.0", "1.1.0"]
+        );
+        assert_eq!(
+            bumped("patch"),
+            ["1.2.1", "1.2.1", "0.3.2", "2.0.1", "2.0.1", "1.0.1"]
+        );
+        assert_eq!(
+            bumped("3.0.0"),
+            ["3.0.0", "3.0.0", "3.0.0", "3.0.0", "3.0.0", "3.0.0"]
+        );
+    }
+}
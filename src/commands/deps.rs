@@ -0,0 +1,155 @@
+use super::print_scan_warnings;
+use crate::addon::{
+    manifest::{version::AddonVersionReq, Dependency},
+    scanner::{scan_world, ScannedPack},
+    selector::Selector,
+};
+use anyhow::Result;
+use console::style;
+use std::path::Path;
+
+enum Resolved<'a> {
+    Pack(&'a ScannedPack),
+    Mismatch(&'a ScannedPack),
+    Missing,
+    Module,
+}
+
+fn resolve<'a>(dep: &Dependency, list: &'a [ScannedPack]) -> Resolved<'a> {
+    let uuid = match &dep.uuid {
+        Some(uuid) => uuid,
+        None => return Resolved::Module,
+    };
+    let candidates = list
+        .iter()
+        .filter(|i| &i.manifest.header.uuid == uuid)
+        .collect::<Vec<_>>();
+    if let Some(pack) = candidates
+        .iter()
+        .find(|i| AddonVersionReq::at_least(&dep.version).matches(&i.manifest.header.version))
+    {
+        Resolved::Pack(pack)
+    } else if let Some(pack) = candidates
+        .iter()
+        .max_by(|a, b| a.manifest.header.version.cmp(&b.manifest.header.version))
+    {
+        Resolved::Mismatch(pack)
+    } else {
+        Resolved::Missing
+    }
+}
+
+fn describe(entry: &ScannedPack) -> String {
+    format!(
+        "{} {} [{}]",
+        style(&entry.name).blue(),
+        style(&entry.manifest.header.version).cyan(),
+        entry.packate_type.get_short_name()
+    )
+}
+
+fn print_dependencies(
+    entry: &ScannedPack,
+    list: &[ScannedPack],
+    prefix: &str,
+    visited: &mut Vec<String>,
+) {
+    let deps = match &entry.manifest.dependencies {
+        Some(deps) => deps,
+        None => return,
+    };
+    for (index, dep) in deps.iter().enumerate() {
+        let last = index + 1 == deps.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        let name = dep
+            .uuid
+            .as_ref()
+            .or(dep.module_name.as_ref())
+            .map(String::as_str)
+            .unwrap_or("<unnamed>");
+        match resolve(dep, list) {
+            Resolved::Module => println!(
+                "{prefix}{branch}{} {} [module]",
+                style(name).cyan(),
+                style(&dep.version).cyan()
+            ),
+            Resolved::Missing => println!(
+                "{prefix}{branch}{} {}",
+                style(name).red(),
+                style(format!("unresolved, requires {}", dep.version)).red()
+            ),
+            Resolved::Mismatch(pack) => println!(
+                "{prefix}{branch}{} {}",
+                describe(pack),
+                style(format!("version mismatch, requires {}", dep.version)).yellow()
+            ),
+            Resolved::Pack(pack) => {
+                if visited.contains(&pack.manifest.header.uuid) {
+                    println!(
+                        "{prefix}{branch}{} {}",
+                        describe(pack),
+                        style("(cycle)").yellow()
+                    );
+                    continue;
+                }
+                println!("{prefix}{branch}{}", describe(pack));
+                visited.push(pack.manifest.header.uuid.clone());
+                print_dependencies(pack, list, &format!("{prefix}{indent}"), visited);
+                visited.pop();
+            }
+        }
+    }
+}
+
+fn required_by<'a>(entry: &ScannedPack, list: &'a [ScannedPack]) -> Vec<&'a ScannedPack> {
+    list.iter()
+        .filter(|i| {
+            i.manifest.dependencies.as_ref().is_some_and(|deps| {
+                deps.iter()
+                    .any(|dep| dep.uuid.as_ref() == Some(&entry.manifest.header.uuid))
+            })
+        })
+        .collect()
+}
+
+pub fn deps<P: AsRef<Path>>(name: Option<String>, target: P, lang: &str) -> Result<()> {
+    let target = target.as_ref();
+    let mut scan = scan_world(target);
+    scan.localize(lang);
+    print_scan_warnings(&scan);
+    let list = scan.into_enabled();
+
+    let selected: Vec<&ScannedPack> = match &name {
+        Some(name) => Selector::parse(name)
+            .select(&list)?
+            .into_iter()
+            .map(|i| &list[i])
+            .collect(),
+        None => list.iter().collect(),
+    };
+
+    for entry in selected {
+        println!("{}", describe(entry));
+        let dependents = required_by(entry, &list);
+        if dependents.is_empty() {
+            println!("  required by: {}", style("-").dim());
+        } else {
+            println!(
+                "  required by: {}",
+                dependents
+                    .iter()
+                    .map(|i| describe(i))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        let mut visited = vec![entry.manifest.header.uuid.clone()];
+        print_dependencies(entry, &list, "", &mut visited);
+        println!();
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use anyhow::{anyhow, Context, Result};
+use copy_dir::copy_dir;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{find_pack_folders, install::get_available_folder, PackFolder};
+use crate::addon::{manifest::PackateType, scaffold};
+
+/// The folder of a forked pack, named after the new name when there is one, as `<name> BP` and
+/// `<name> RP` for a pair.
+fn fork_folder(pack: &PackFolder, name: Option<&str>, single: bool, dir: &Path) -> PathBuf {
+    let folder = match name {
+        Some(name) if single => name.to_string(),
+        Some(name) => match PackateType::try_from(&pack.manifest) {
+            Ok(PackateType::Behavior) => format!("{name} BP"),
+            Ok(PackateType::Resource) => format!("{name} RP"),
+            Err(_) => name.to_string(),
+        },
+        None => pack
+            .folder
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+    };
+    get_available_folder(folder, dir)
+}
+
+/// The absolute path of `dir`, which may not exist yet, with the links of the existing part
+/// resolved.
+fn resolve(dir: &Path) -> Result<PathBuf> {
+    let existing = dir
+        .ancestors()
+        .find(|i| i.exists())
+        .unwrap_or(Path::new("."));
+    let rest = dir.strip_prefix(existing).unwrap_or(dir);
+    Ok(existing
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {}", dir.display()))?
+        .join(rest))
+}
+
+/// Copy the packs in `paths` into `dir` with new uuids, so that the copies can be installed next
+/// to the original packs. Packs forked together keep depending on each other.
+pub fn fork<P: AsRef<Path>, D: AsRef<Path>>(paths: &[P], name: Option<&str>, dir: D) -> Result<()> {
+    let dir = resolve(dir.as_ref())?;
+    let dir = dir.as_path();
+    let mut folders = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        // so that `.` has a folder name to name the fork after
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", path.display()))?;
+        folders.extend(find_pack_folders(&path)?);
+    }
+    folders.sort();
+    folders.dedup();
+    if folders.is_empty() {
+        return Err(anyhow!("No pack found"));
+    }
+    if let Some(folder) = folders.iter().find(|i| dir.starts_with(i)) {
+        return Err(anyhow!(
+            "Can not fork {} into {}, which is inside of it",
+            folder.display(),
+            dir.display()
+        ));
+    }
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut packs = folders
+        .into_iter()
+        .map(PackFolder::read)
+        .collect::<Result<Vec<_>>>()?;
+    let origins = packs
+        .iter()
+        .map(|i| {
+            (
+                i.manifest.header.name.clone(),
+                i.manifest.header.uuid.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    scaffold::fork(
+        &mut packs
+            .iter_mut()
+            .map(|i| &mut i.manifest)
+            .collect::<Vec<_>>(),
+    )?;
+    if let Some(name) = name {
+        packs
+            .iter_mut()
+            .for_each(|i| i.manifest.header.name = name.to_string());
+    }
+    let single = packs.len() == 1;
+
+    for (pack, (origin_name, origin_uuid)) in packs.iter().zip(origins) {
+        let folder = fork_folder(pack, name, single, dir);
+        copy_dir(&pack.folder, &folder).with_context(|| {
+            format!(
+                "Failed to copy {} to {}",
+                pack.folder.display(),
+                folder.display()
+            )
+        })?;
+        pack.write(&folder)?;
+        println!(
+            "Forked {origin_name} [{origin_uuid}] as {} [{}] in {}",
+            pack.manifest.header.name,
+            pack.manifest.header.uuid,
+            folder.display()
+        );
+    }
+    Ok(())
+}
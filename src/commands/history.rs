@@ -0,0 +1,80 @@
+use anyhow::Result;
+use prettytable::{Cell, Row, Table};
+use std::path::Path;
+
+use super::print_titled_table;
+use crate::addon::{
+    manifest::PackateType,
+    scanner::{scan_world_packs, ScannedPack},
+    trash,
+    world_packet_list::{read_history, History},
+};
+
+/// What became of a pack recorded in the history.
+fn status(history: &History, scan: &[ScannedPack], trashed: bool) -> String {
+    let installed = scan
+        .iter()
+        .filter(|i| i.manifest.header.uuid == history.uuid)
+        .collect::<Vec<_>>();
+    let pack = installed
+        .iter()
+        .find(|i| i.is_enabled())
+        .or(installed.first());
+    match pack {
+        Some(pack) => {
+            let status = if pack.is_enabled() {
+                "in use"
+            } else {
+                "installed"
+            };
+            if pack.manifest.header.version == history.version {
+                status.to_string()
+            } else {
+                format!("{status} as {}", pack.manifest.header.version)
+            }
+        }
+        None if trashed => "in trash".to_string(),
+        None => "removed".to_string(),
+    }
+}
+
+/// Print the packs a world has ever used, as recorded in its history files, and what became of them.
+pub fn history<P: AsRef<Path>>(target: P, resource: bool, behavior: bool) -> Result<()> {
+    let (res, beh) = match (resource, behavior) {
+        (false, false) => (true, true),
+        a => a,
+    };
+    let target = target.as_ref();
+    let trashed = trash::list(target)?;
+    let mut tab = Table::new();
+    tab.set_titles(Row::new(
+        ["name", "type", "version", "uuid", "status"]
+            .iter()
+            .map(|i| Cell::new(i).style_spec("Fm"))
+            .collect(),
+    ));
+    for (packate_type, _) in [(PackateType::Resource, res), (PackateType::Behavior, beh)]
+        .into_iter()
+        .filter(|i| i.1)
+    {
+        let scan = scan_world_packs(target, packate_type);
+        for history in read_history(target, packate_type)?.packs {
+            let in_trash = trashed
+                .iter()
+                .any(|i| i.uuid == history.uuid && i.packate_type == packate_type);
+            tab.add_row(Row::new(vec![
+                Cell::new(&history.name).style_spec("Fb"),
+                Cell::new(packate_type.get_short_name()).style_spec("Fc"),
+                Cell::new(&history.version.to_string()).style_spec("Fc"),
+                Cell::new(&history.uuid).style_spec("Fy"),
+                Cell::new(&status(&history, &scan.packs, in_trash)),
+            ]));
+        }
+    }
+    if tab.is_empty() {
+        println!("No pack was recorded in the history of the world");
+        return Ok(());
+    }
+    print_titled_table(&mut tab);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use prettytable::{row, table};
+use std::{fs, path::Path};
+
+use super::print_kv_table;
+use crate::{find_world, get_worlds, read_level_name, Server, WorkDirType, DEFAULT_LEVEL_NAME};
+
+fn level_name(world: &Path) -> String {
+    let folder = world
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    match fs::read_to_string(world.join("levelname.txt")) {
+        Ok(name) if name.trim() != folder => format!("{folder} ({})", name.trim()),
+        _ => folder,
+    }
+}
+
+/// Show what the working directory was detected as, and the server layout it belongs to.
+pub fn info<P: AsRef<Path>>(
+    workdir: P,
+    work_dir_type: &WorkDirType,
+    server: Option<&Server>,
+) -> Result<()> {
+    let workdir = workdir.as_ref();
+    let or_none = |i: Option<String>| i.unwrap_or("-".to_string());
+    let mut tab = table!(
+        [Fm->"work dir", workdir.display()],
+        [
+            Fm->"type",
+            Fc->match work_dir_type {
+                WorkDirType::Bds => "bds",
+                WorkDirType::Level => "level",
+            }
+        ],
+        [Fm->"server", Fb->or_none(server.map(|i| i.layout.to_string()))],
+        [Fm->"server root", or_none(server.map(|i| i.root.display().to_string()))],
+        [
+            Fm->"binary",
+            or_none(
+                server
+                    .and_then(|i| i.binary.as_ref())
+                    .map(|i| i.display().to_string())
+            )
+        ]
+    );
+    match (work_dir_type, server) {
+        (WorkDirType::Level, _) => {
+            tab.add_row(row![Fm->"level", level_name(workdir)]);
+        }
+        (WorkDirType::Bds, Some(server)) => {
+            let worlds = get_worlds(&server.root)?
+                .iter()
+                .map(|i| level_name(i))
+                .collect::<Vec<_>>();
+            tab.add_row(row![Fm->"worlds", worlds.join("\n")]);
+            let default = read_level_name(&server.root);
+            let world = default.as_deref().unwrap_or(DEFAULT_LEVEL_NAME);
+            tab.add_row(row![
+                Fm->"default world",
+                match (&default, find_world(&server.root, world)) {
+                    (Some(_), Ok(_)) => format!("{world} (level-name)"),
+                    (None, Ok(_)) => world.to_string(),
+                    (_, Err(_)) => format!("{world} (missing)"),
+                }
+            ]);
+        }
+        (WorkDirType::Bds, None) => {}
+    }
+    print_kv_table(&mut tab);
+    Ok(())
+}
@@ -1,6 +1,6 @@
+use crate::addon::manifest;
 use crate::addon::manifest::PackateType;
-use crate::addon::world_packet_list::{to_packet_list_string, InUse};
-use crate::addon::{self, manifest};
+use crate::addon::world_packet_list::{read_packet_list, record_history, write_packet_list, InUse};
 use anyhow::{anyhow, Context, Result};
 use copy_dir::copy_dir;
 use std::fs;
@@ -9,7 +9,7 @@ use std::path::{Path, PathBuf};
 use tempfile::tempdir;
 use zip::ZipArchive;
 
-fn get_available_folder<S: AsRef<str>, P: AsRef<Path>>(name: S, target: P) -> PathBuf {
+pub(crate) fn get_available_folder<S: AsRef<str>, P: AsRef<Path>>(name: S, target: P) -> PathBuf {
     let default = target.as_ref().join(name.as_ref());
     if default.exists() {
         let mut num = 0;
@@ -18,7 +18,7 @@ fn get_available_folder<S: AsRef<str>, P: AsRef<Path>>(name: S, target: P) -> Pa
             .join(format!("{}({})", name.as_ref(), num))
             .exists()
         {
-            num = num + 1
+            num += 1
         }
         return target.as_ref().join(format!("{}({})", name.as_ref(), num));
     }
@@ -35,37 +35,20 @@ fn install_single_pack<P: AsRef<Path>>(dir: &fs::DirEntry, target: P) -> Result<
         .with_context(|| "Failed to successfully read package manifest.json information")?;
         let packate_type = PackateType::try_from(&manifest_info)
             .with_context(|| "Installation of world_template is not supported")?;
-        let mut packetlist = if target.join(packate_type.get_list_file_string()).exists() {
-            addon::world_packet_list::parse_in_use_packet_list(
-                fs::read_to_string(target.join(packate_type.get_list_file_string())).with_context(
-                    || format!("Failed to read {}", packate_type.get_list_file_string()),
-                )?,
-            )
-            .with_context(|| {
-                format!(
-                    "Failed to properly parse an existing {}",
-                    packate_type.get_list_file_string()
-                )
-            })?
-        } else {
-            Vec::new()
-        };
+        let mut packetlist = read_packet_list(target, packate_type)?;
         match packetlist
             .iter()
             .find(|i| i.pack_id == manifest_info.header.uuid)
         {
             Some(i) if i.version == manifest_info.header.version => println!(
                 "addon {} [version: {}] already exists, skip installation",
-                manifest_info.header.name,
-                manifest_info.header.version.to_string()
+                manifest_info.header.name, manifest_info.header.version
             ),
 
             Some(i) if i.version > manifest_info.header.version => println!(
                 "A newer version of addon {} [version: {}] already exists.\
                     The installation of the current version {} has been skipped.",
-                manifest_info.header.name,
-                i.version.to_string(),
-                manifest_info.header.version.to_string()
+                manifest_info.header.name, i.version, manifest_info.header.version
             ),
 
             None | Some(_) => {
@@ -83,14 +66,18 @@ fn install_single_pack<P: AsRef<Path>>(dir: &fs::DirEntry, target: P) -> Result<
                 }
                 copy_dir(dir.path(), targe_dir).with_context(|| "while copy")?;
                 let version_str = manifest_info.header.version.to_string();
-                packetlist.push(InUse {
-                    pack_id: manifest_info.header.uuid,
-                    version: manifest_info.header.version,
-                });
-                fs::write(
-                    target.join(packate_type.get_list_file_string()),
-                    to_packet_list_string(packetlist)?,
+                record_history(
+                    target,
+                    packate_type,
+                    &manifest_info.header.name,
+                    &manifest_info.header.uuid,
+                    &manifest_info.header.version,
                 )?;
+                packetlist.push(InUse::new(
+                    manifest_info.header.uuid,
+                    manifest_info.header.version,
+                ));
+                write_packet_list(target, packate_type, packetlist)?;
                 println!(
                     "success to install {} [{}]",
                     manifest_info.header.name, version_str
@@ -106,7 +93,7 @@ fn install_mcaddon<P: AsRef<Path>>(mut addon: ZipArchive<fs::File>, target: P) -
     let temp_dir = tempdir().with_context(|| "fail to create temp dir")?;
     let temp_path = temp_dir.path();
     addon
-        .extract(&temp_path)
+        .extract(temp_path)
         .with_context(|| "fail to extract the zip file")?;
     for i in temp_path
         .read_dir()
@@ -153,35 +140,18 @@ pub fn install<P: AsRef<Path>, P1: AsRef<Path>>(addon: P, target: P1) -> Result<
     let packate_type = PackateType::try_from(&data)
         .with_context(|| "Installation of world_template is not supported")?;
 
-    let mut packetlist = if target.join(packate_type.get_list_file_string()).exists() {
-        addon::world_packet_list::parse_in_use_packet_list(
-            fs::read_to_string(target.join(packate_type.get_list_file_string())).with_context(
-                || format!("Failed to read {}", packate_type.get_list_file_string()),
-            )?,
-        )
-        .with_context(|| {
-            format!(
-                "Failed to properly parse an existing {}",
-                packate_type.get_list_file_string()
-            )
-        })?
-    } else {
-        Vec::new()
-    };
+    let mut packetlist = read_packet_list(target, packate_type)?;
 
     match packetlist.iter().find(|i| i.pack_id == data.header.uuid) {
         Some(i) if i.version == data.header.version => println!(
             "addon {} [version: {}] already exists, skip installation",
-            data.header.name,
-            data.header.version.to_string()
+            data.header.name, data.header.version
         ),
 
         Some(i) if i.version > data.header.version => println!(
             "A newer version of addon {} [version: {}] already exists.\
                 The installation of the current version {} has been skipped.",
-            data.header.name,
-            i.version.to_string(),
-            data.header.version.to_string()
+            data.header.name, i.version, data.header.version
         ),
 
         None | Some(_) => {
@@ -197,14 +167,15 @@ pub fn install<P: AsRef<Path>, P1: AsRef<Path>>(addon: P, target: P1) -> Result<
                     )
                 })?;
             let version_str = data.header.version.to_string();
-            packetlist.push(InUse {
-                pack_id: data.header.uuid,
-                version: data.header.version,
-            });
-            fs::write(
-                target.join(packate_type.get_list_file_string()),
-                to_packet_list_string(packetlist)?,
+            record_history(
+                target,
+                packate_type,
+                &data.header.name,
+                &data.header.uuid,
+                &data.header.version,
             )?;
+            packetlist.push(InUse::new(data.header.uuid, data.header.version));
+            write_packet_list(target, packate_type, packetlist)?;
             println!("success to install {} [{}]", data.header.name, version_str)
         }
     }
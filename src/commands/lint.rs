@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Context, Result};
+use console::style;
+use prettytable::{Cell, Row, Table};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::tempdir;
+
+use super::{find_pack_folders, print_titled_table};
+
+use crate::addon::{
+    lint::{LintConfig, LintTarget, Linter, Severity, RULES},
+    manifest::Manifest,
+};
+
+/// The default severities with the ones given on the command line, later levels win.
+pub fn config(allow: &[String], warn: &[String], deny: &[String]) -> Result<LintConfig> {
+    let mut config = LintConfig::default();
+    for (rules, severity) in [
+        (allow, Severity::Allow),
+        (warn, Severity::Warn),
+        (deny, Severity::Deny),
+    ] {
+        for rule in rules {
+            config
+                .set(rule, severity)
+                .with_context(|| "see `lint --list-rules` for the known rules")?;
+        }
+    }
+    Ok(config)
+}
+
+fn read_target(name: String, folder: &Path) -> LintTarget {
+    let path = folder.join("manifest.json");
+    LintTarget {
+        name,
+        manifest: fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))
+            .and_then(|i| Manifest::new(i).map_err(|err| err.to_string())),
+    }
+}
+
+/// The pack in `dir`, or the packs in its sub folders when it is not a pack itself.
+fn collect(dir: &Path, prefix: &str) -> Result<Vec<LintTarget>> {
+    Ok(find_pack_folders(dir)?
+        .iter()
+        .map(|i| match i.strip_prefix(dir) {
+            Ok(name) if !name.as_os_str().is_empty() => {
+                read_target(format!("{prefix}/{}", name.display()), i)
+            }
+            _ => read_target(prefix.to_string(), i),
+        })
+        .collect())
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|i| i.to_str())
+        .is_some_and(|i| matches!(i.to_lowercase().as_str(), "zip" | "mcpack" | "mcaddon"))
+}
+
+/// Check the manifests of a pack folder, a folder of packs, a `manifest.json` or an archive,
+/// failing when a rule set to deny finds something.
+pub fn lint<P: AsRef<Path>>(path: P, config: &LintConfig) -> Result<()> {
+    let path = path.as_ref();
+    let name = path
+        .file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string();
+    let targets = if path.is_dir() {
+        collect(path, &name)?
+    } else if path.file_name().is_some_and(|i| i == "manifest.json") {
+        let folder = path.parent().map(PathBuf::from).unwrap_or_default();
+        vec![read_target(path.display().to_string(), &folder)]
+    } else if is_archive(path) {
+        let temp_dir = tempdir().with_context(|| "fail to create temp dir")?;
+        let file =
+            fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        zip::ZipArchive::new(file)
+            .with_context(|| format!("Unable to read zip file {}", path.display()))?
+            .extract(temp_dir.path())
+            .with_context(|| "fail to extract the zip file")?;
+        collect(temp_dir.path(), &name)?
+    } else {
+        return Err(anyhow!(
+            "{} is not a pack folder, a manifest.json or an archive",
+            path.display()
+        ));
+    };
+    if targets.is_empty() {
+        return Err(anyhow!("No pack found in {}", path.display()));
+    }
+
+    let findings = Linter::new(config).lint(&targets);
+    for finding in &findings {
+        let level = match finding.severity {
+            Severity::Deny => style("error").red().bold(),
+            _ => style("warning").yellow().bold(),
+        };
+        println!(
+            "{level}[{}]: {}: {}",
+            finding.rule, finding.pack, finding.message
+        );
+    }
+    let errors = findings
+        .iter()
+        .filter(|i| i.severity == Severity::Deny)
+        .count();
+    let warnings = findings.len() - errors;
+    println!(
+        "checked {} pack(s): {errors} error(s), {warnings} warning(s)",
+        targets.len()
+    );
+    if errors > 0 {
+        return Err(anyhow!("{} failed the lint", path.display()));
+    }
+    Ok(())
+}
+
+pub fn print_rules(config: &LintConfig) {
+    let mut tab = Table::new();
+    tab.set_titles(Row::new(
+        ["rule", "severity", "description"]
+            .iter()
+            .map(|i| Cell::new(i).style_spec("Fm"))
+            .collect(),
+    ));
+    for rule in RULES {
+        let severity = config.severity(rule.id);
+        tab.add_row(Row::new(vec![
+            Cell::new(rule.id).style_spec("Fb"),
+            Cell::new(&severity.to_string()).style_spec(match severity {
+                Severity::Deny => "Fr",
+                Severity::Warn => "Fy",
+                Severity::Allow => "",
+            }),
+            Cell::new(rule.description),
+        ]));
+    }
+    print_titled_table(&mut tab);
+}
@@ -1,76 +1,455 @@
-use crate::addon::{self, manifest::Manifest, world_packet_list::*};
-use addon::manifest::PackateType;
-use anyhow::Result;
-use prettytable::{
-    format::{self, LineSeparator},
-    table,
+use super::{print_kv_table, print_scan_warnings, print_titled_table};
+use crate::addon::{
+    manifest::{
+        version::{AddonVersion, AddonVersionReq},
+        ModuleType, PackateType,
+    },
+    scanner::{scan_server, scan_world_packs, Scan, ScannedPack},
 };
-use std::{fs, path::Path};
+use crate::get_worlds;
+use anyhow::Result;
+use clap::ValueEnum;
+use prettytable::{table, Cell, Row, Table};
+use regex::Regex;
+use std::{collections::BTreeMap, path::Path};
 
-fn print_list<P: AsRef<Path>>(list_type: PackateType, target: P) -> Result<()> {
-    let target = target.as_ref();
-    let list = parse_in_use_packet_list(fs::read_to_string(
-        target.join(list_type.get_list_file_string()),
-    )?)?;
-    for i in target.join(list_type.get_path_name()).read_dir()?.flatten() {
-        if let Ok(typ) = i.file_type() {
-            if typ.is_dir() {
-                let data = Manifest::new(fs::read_to_string(i.path().join("manifest.json"))?)?;
-                if list
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Column {
+    Name,
+    Version,
+    Uuid,
+    Type,
+    Folder,
+    Status,
+    /// The format_version of the manifest
+    Format,
+}
+
+impl Column {
+    fn title(&self) -> &str {
+        match self {
+            Column::Name => "name",
+            Column::Version => "version",
+            Column::Uuid => "uuid",
+            Column::Type => "type",
+            Column::Folder => "folder",
+            Column::Status => "status",
+            Column::Format => "format",
+        }
+    }
+    fn style(&self) -> &str {
+        match self {
+            Column::Name => "Fb",
+            Column::Version | Column::Type | Column::Format => "Fc",
+            Column::Uuid => "Fy",
+            Column::Folder | Column::Status => "",
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortKey {
+    Name,
+    Version,
+    Type,
+    /// The order in the world's pack list, which is the order the game applies them in
+    Priority,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModuleFilter {
+    Script,
+    Data,
+    Resources,
+}
+
+impl From<ModuleFilter> for ModuleType {
+    fn from(value: ModuleFilter) -> Self {
+        match value {
+            ModuleFilter::Script => ModuleType::Script,
+            ModuleFilter::Data => ModuleType::Data,
+            ModuleFilter::Resources => ModuleType::Resources,
+        }
+    }
+}
+
+/// Conditions a pack has to meet to be listed, all of the set ones have to match.
+#[derive(Default)]
+pub struct PackFilter {
+    /// Case-insensitive substring of the name or the description.
+    pub search: Option<String>,
+    /// Pattern matched against the name and the description.
+    pub regex: Option<Regex>,
+    /// Case-insensitive substring of one of the authors.
+    pub author: Option<String>,
+    /// The pack has to contain a module of every listed type.
+    pub module_types: Vec<ModuleFilter>,
+    pub uuid_prefix: Option<String>,
+    pub version: Option<AddonVersionReq>,
+    /// The manifest has to use one of the listed format versions.
+    pub format_versions: Vec<u32>,
+}
+
+impl PackFilter {
+    pub fn is_empty(&self) -> bool {
+        self.search.is_none()
+            && self.regex.is_none()
+            && self.author.is_none()
+            && self.module_types.is_empty()
+            && self.uuid_prefix.is_none()
+            && self.version.is_none()
+            && self.format_versions.is_empty()
+    }
+
+    pub fn matches(&self, pack: &ScannedPack) -> bool {
+        let manifest = &pack.manifest;
+        let header = &manifest.header;
+        let texts = [Some(&pack.name), pack.description.as_ref()];
+        if let Some(search) = &self.search {
+            let search = search.to_lowercase();
+            if !texts
+                .iter()
+                .flatten()
+                .any(|i| i.to_lowercase().contains(&search))
+            {
+                return false;
+            }
+        }
+        if let Some(regex) = &self.regex {
+            if !texts.iter().flatten().any(|i| regex.is_match(i)) {
+                return false;
+            }
+        }
+        if let Some(author) = &self.author {
+            let author = author.to_lowercase();
+            let authors = manifest.metadata.as_ref().and_then(|i| i.authors.as_ref());
+            if !authors.is_some_and(|i| {
+                i.as_slice()
                     .iter()
-                    .any(|i| i.pack_id == data.header.uuid && i.version == data.header.version)
-                {
-                    let mut tab = table!(
-                        [Fm->"name", Fb->data.header.name],
-                        [Fm->"version", Fc->data.header.version],
-                        [Fm->"uuid", Fy->data.header.uuid],
-                        [Fm->"type", Fc->list_type.get_path_name().replace("_", " ")],
-                        [
-                            Fm->"description",
-                            if let Some(a) = data.header.description {
-                                a
-                            } else {
-                                "".to_string()
-                            }
-                        ]
-                    );
-                    tab.set_format(
-                        format::FormatBuilder::new()
-                            .column_separator('|')
-                            .padding(1, 1)
-                            .separator(
-                                format::LinePosition::Bottom,
-                                LineSeparator::new(' ', ' ', ' ', ' '),
-                            )
-                            .build(),
-                    );
-                    tab.printstd()
-                }
+                    .any(|i| i.to_lowercase().contains(&author))
+            }) {
+                return false;
+            }
+        }
+        if !self
+            .module_types
+            .iter()
+            .all(|i| manifest.get_type().contains(&(*i).into()))
+        {
+            return false;
+        }
+        if let Some(prefix) = &self.uuid_prefix {
+            if !header
+                .uuid
+                .to_lowercase()
+                .starts_with(&prefix.to_lowercase())
+            {
+                return false;
+            }
+        }
+        if let Some(version) = &self.version {
+            if !version.matches(&header.version) {
+                return false;
             }
         }
+        if !self.format_versions.is_empty()
+            && !self
+                .format_versions
+                .contains(&manifest.format_version().into())
+        {
+            return false;
+        }
+        true
+    }
+}
+
+pub struct ListOptions {
+    pub resource: bool,
+    pub behavior: bool,
+    /// Print a separate table for every pack instead of one row per pack.
+    pub long: bool,
+    /// Do not truncate values, and show every column unless `columns` is set.
+    pub wide: bool,
+    pub columns: Vec<Column>,
+    pub sort: SortKey,
+    pub filter: PackFilter,
+    /// The language used to resolve the names and the descriptions of the packs.
+    pub lang: String,
+    /// Also show installed packs that are not in use.
+    pub disabled: bool,
+}
+
+const DEFAULT_COLUMNS: [Column; 4] = [Column::Name, Column::Version, Column::Type, Column::Status];
+const WIDE_COLUMNS: [Column; 7] = [
+    Column::Name,
+    Column::Version,
+    Column::Uuid,
+    Column::Type,
+    Column::Format,
+    Column::Folder,
+    Column::Status,
+];
+const NAME_WIDTH: usize = 32;
+const UUID_WIDTH: usize = 8;
+
+fn get_cell(pack: &ScannedPack, column: Column, wide: bool) -> String {
+    let text = match column {
+        Column::Name => pack.name.clone(),
+        Column::Version => pack.manifest.header.version.to_string(),
+        Column::Uuid => pack.manifest.header.uuid.clone(),
+        Column::Type => pack.packate_type.get_short_name().to_string(),
+        Column::Folder => pack.folder_name(),
+        Column::Status => match (pack.is_enabled(), pack.diagnostics.is_empty()) {
+            (true, true) => "enabled",
+            (true, false) => "enabled (!)",
+            (false, true) => "disabled",
+            (false, false) => "disabled (!)",
+        }
+        .to_string(),
+        Column::Format => pack.manifest.format_version().to_string(),
+    };
+    match column {
+        Column::Name if !wide => truncate(&text, NAME_WIDTH),
+        Column::Uuid if !wide => text.chars().take(UUID_WIDTH).collect(),
+        _ => text,
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() > width {
+        let mut out = text.chars().take(width - 1).collect::<String>();
+        out.push('…');
+        out
+    } else {
+        text.to_string()
     }
-    Ok(())
 }
 
-pub fn list<P: AsRef<Path>>(target: P, resource: bool, behavior: bool) -> Result<()> {
-    let (res, beh) = match (resource, behavior) {
+fn sort_list(list: &mut [ScannedPack], key: SortKey) {
+    // resource packs are listed first, keep that for entries that compare equal
+    let type_order = |i: &ScannedPack| i.packate_type == PackateType::Behavior;
+    match key {
+        SortKey::Name => list.sort_by_cached_key(|i| i.name.to_lowercase()),
+        SortKey::Version => {
+            list.sort_by(|a, b| a.manifest.header.version.cmp(&b.manifest.header.version))
+        }
+        SortKey::Type => list.sort_by_cached_key(|i| (type_order(i), i.name.to_lowercase())),
+        // packs that are not in use go last
+        SortKey::Priority => {
+            list.sort_by_key(|i| (type_order(i), i.priority.is_none(), i.priority))
+        }
+    }
+}
+
+fn print_long(entry: ScannedPack) {
+    let mut tab = table!(
+        [Fm->"name", Fb->entry.name],
+        [Fm->"version", Fc->entry.manifest.header.version],
+        [Fm->"uuid", Fy->entry.manifest.header.uuid],
+        [Fm->"type", Fc->entry.packate_type.get_path_name().replace("_", " ")],
+        [Fm->"format", Fc->entry.manifest.format_version()],
+        [
+            Fm->"description",
+            if let Some(a) = entry.description {
+                a
+            } else {
+                "".to_string()
+            }
+        ]
+    );
+    print_kv_table(&mut tab)
+}
+
+fn print_compact(list: &[ScannedPack], columns: &[Column], wide: bool) {
+    let mut tab = Table::new();
+    tab.set_titles(Row::new(
+        columns
+            .iter()
+            .map(|i| Cell::new(i.title()).style_spec("Fm"))
+            .collect(),
+    ));
+    for entry in list {
+        tab.add_row(Row::new(
+            columns
+                .iter()
+                .map(|i| Cell::new(&get_cell(entry, *i, wide)).style_spec(i.style()))
+                .collect(),
+        ));
+    }
+    print_titled_table(&mut tab)
+}
+
+pub fn list<P: AsRef<Path>>(target: P, options: ListOptions) -> Result<()> {
+    let (res, beh) = match (options.resource, options.behavior) {
         (false, false) => (true, true),
         a => a,
     };
     let target = target.as_ref();
-    if target
-        .join(PackateType::Resource.get_list_file_string())
-        .exists()
-        && res
-    {
-        print_list(PackateType::Resource, target)?;
+    let mut scan = Scan::default();
+    if res {
+        scan.extend(scan_world_packs(target, PackateType::Resource));
     }
-    if target
-        .join(PackateType::Behavior.get_list_file_string())
-        .exists()
-        && beh
+    if beh {
+        scan.extend(scan_world_packs(target, PackateType::Behavior));
+    }
+    scan.localize(&options.lang);
+    scan.packs
+        .retain(|i| (i.is_enabled() || options.disabled) && options.filter.matches(i));
+    print_scan_warnings(&scan);
+    let mut entries = scan.packs;
+    sort_list(&mut entries, options.sort);
+
+    if options.long {
+        entries.into_iter().for_each(print_long);
+        return Ok(());
+    }
+    let columns = match (options.columns.is_empty(), options.wide) {
+        (false, _) => options.columns.as_slice(),
+        (true, false) => DEFAULT_COLUMNS.as_slice(),
+        (true, true) => WIDE_COLUMNS.as_slice(),
+    };
+    print_compact(&entries, columns, options.wide);
+    Ok(())
+}
+
+struct MatrixRow {
+    pack: Option<ScannedPack>,
+    packate_type: PackateType,
+    /// The versions found at the server level, followed by the versions in use in every world.
+    cells: Vec<Vec<AddonVersion>>,
+}
+
+impl MatrixRow {
+    fn is_drifted(&self) -> bool {
+        let worlds = &self.cells[1..];
+        worlds.iter().any(|i| i != &worlds[0])
+    }
+}
+
+pub fn list_all_worlds<P: AsRef<Path>>(bds_root: P, options: ListOptions) -> Result<()> {
+    let (res, beh) = match (options.resource, options.behavior) {
+        (false, false) => (true, true),
+        a => a,
+    };
+    let bds_root = bds_root.as_ref();
+    let worlds = get_worlds(bds_root)?;
+    let mut server = scan_server(bds_root);
+    server.localize(&options.lang);
+    print_scan_warnings(&server);
+    let mut rows = BTreeMap::<(bool, String), MatrixRow>::new();
+    let new_row = |packate_type: PackateType| MatrixRow {
+        pack: None,
+        packate_type,
+        cells: vec![Vec::new(); worlds.len() + 1],
+    };
+    let key = |packate_type: PackateType, uuid: &str| {
+        (packate_type == PackateType::Behavior, uuid.to_string())
+    };
+
+    for packate_type in [PackateType::Resource, PackateType::Behavior]
+        .into_iter()
+        .filter(|i| match i {
+            PackateType::Resource => res,
+            PackateType::Behavior => beh,
+        })
     {
-        print_list(PackateType::Behavior, target)?;
+        for pack in server
+            .packs
+            .iter()
+            .filter(|i| i.packate_type == packate_type)
+        {
+            let row = rows
+                .entry(key(packate_type, &pack.manifest.header.uuid))
+                .or_insert_with(|| new_row(packate_type));
+            row.cells[0].push(pack.manifest.header.version.clone());
+            row.pack.get_or_insert(pack.clone());
+        }
+        for (index, world) in worlds.iter().enumerate() {
+            let mut scan = scan_world_packs(world, packate_type);
+            scan.localize(&options.lang);
+            print_scan_warnings(&scan);
+            for pack in &scan.packs {
+                rows.entry(key(packate_type, &pack.manifest.header.uuid))
+                    .or_insert_with(|| new_row(packate_type))
+                    .pack
+                    .get_or_insert(pack.clone());
+            }
+            let in_use = scan
+                .enabled()
+                .filter_map(|i| i.in_use.clone())
+                .chain(scan.missing.iter().map(|i| i.in_use.clone()));
+            for i in in_use {
+                rows.entry(key(packate_type, &i.pack_id))
+                    .or_insert_with(|| new_row(packate_type))
+                    .cells[index + 1]
+                    .push(i.version);
+            }
+        }
+    }
+
+    let mut tab = Table::new();
+    let mut titles = vec![
+        Cell::new("name").style_spec("Fm"),
+        Cell::new("type").style_spec("Fm"),
+        Cell::new("server").style_spec("Fm"),
+    ];
+    titles.extend(
+        worlds.iter().map(|i| {
+            Cell::new(&i.file_name().unwrap_or_default().to_string_lossy()).style_spec("Fm")
+        }),
+    );
+    tab.set_titles(Row::new(titles));
+    let mut rows = rows
+        .into_iter()
+        // packs that are only present in a world folder but not in use anywhere are not interesting here
+        .filter(|(_, i)| i.cells.iter().any(|i| !i.is_empty()))
+        .filter(|(_, i)| match &i.pack {
+            Some(pack) => options.filter.matches(pack),
+            None => options.filter.is_empty(),
+        })
+        .map(|((_, uuid), row)| {
+            let name = row
+                .pack
+                .as_ref()
+                .map(|i| i.name.clone())
+                .unwrap_or_else(|| uuid.clone());
+            (name, uuid, row)
+        })
+        .collect::<Vec<_>>();
+    // by type and name as `list --sort type`, the uuid only breaks ties
+    rows.sort_by_cached_key(|(name, uuid, row)| {
+        (
+            row.packate_type == PackateType::Behavior,
+            name.to_lowercase(),
+            uuid.clone(),
+        )
+    });
+    for (name, _, row) in rows {
+        let mut cells = vec![
+            Cell::new(&if options.wide {
+                name
+            } else {
+                truncate(&name, NAME_WIDTH)
+            })
+            .style_spec("Fb"),
+            Cell::new(row.packate_type.get_short_name()).style_spec("Fc"),
+        ];
+        let drifted = row.is_drifted();
+        cells.extend(row.cells.iter().map(|i| {
+            if i.is_empty() {
+                Cell::new("-")
+            } else {
+                Cell::new(
+                    &i.iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                )
+                .style_spec(if drifted { "Fy" } else { "Fc" })
+            }
+        }));
+        tab.add_row(Row::new(cells));
     }
+    print_titled_table(&mut tab);
     Ok(())
 }
@@ -0,0 +1,107 @@
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use semver::Version;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::addon::{
+    json_style::JsonStyle,
+    manifest::{Manifest, PackateType},
+    scaffold::{self, Template, SCRIPT_ENTRY, SERVER_MODULE},
+};
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PackKind {
+    /// A behavior pack with a data module.
+    #[value(alias = "bp")]
+    Behavior,
+    /// A resource pack.
+    #[value(alias = "rp")]
+    Resource,
+    /// A behavior and a resource pack depending on each other.
+    Pair,
+    /// A behavior pack with a script module.
+    Script,
+}
+
+/// Read `1.21.50` as the array form used by header versions.
+pub fn parse_version_array(str: &str) -> Result<[u64; 3], semver::Error> {
+    let version = Version::parse(str.trim())?;
+    Ok([version.major, version.minor, version.patch])
+}
+
+fn script(name: &str) -> String {
+    format!(
+        "import {{ world }} from \"{SERVER_MODULE}\";\n\
+        \n\
+        world.afterEvents.playerSpawn.subscribe(({{ player, initialSpawn }}) => {{\n\
+        \x20 if (initialSpawn) {{\n\
+        \x20   player.sendMessage(\"Hello from {name}!\");\n\
+        \x20 }}\n\
+        }});\n"
+    )
+}
+
+fn write_pack(folder: &Path, manifest: &Manifest) -> Result<()> {
+    fs::create_dir_all(folder).with_context(|| format!("Failed to create {}", folder.display()))?;
+    let path = folder.join("manifest.json");
+    fs::write(&path, JsonStyle::pretty().to_string(manifest)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    if manifest
+        .modules
+        .iter()
+        .any(|i| i.entry.as_deref() == Some(SCRIPT_ENTRY))
+    {
+        let path = folder.join(SCRIPT_ENTRY);
+        fs::create_dir_all(path.parent().unwrap_or(folder))?;
+        fs::write(&path, script(&manifest.header.name))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    println!(
+        "Created {} pack {} [{}] in {}",
+        PackateType::try_from(manifest)
+            .map(|i| i.get_short_name().to_string())
+            .unwrap_or_default(),
+        manifest.header.name,
+        manifest.header.uuid,
+        folder.display()
+    );
+    Ok(())
+}
+
+/// Create the folders of a new pack, or of a pair of packs named `<name> BP` and `<name> RP`, in `dir`.
+pub fn new<P: AsRef<Path>>(
+    kind: PackKind,
+    template: &Template,
+    server_version: Version,
+    dir: P,
+) -> Result<()> {
+    let dir = dir.as_ref();
+    let packs = match kind {
+        PackKind::Behavior => vec![(template.name.clone(), template.behavior_pack())],
+        PackKind::Resource => vec![(template.name.clone(), template.resource_pack())],
+        PackKind::Script => vec![(template.name.clone(), template.script_pack(server_version))],
+        PackKind::Pair => {
+            let (mut bp, mut rp) = (template.behavior_pack(), template.resource_pack());
+            scaffold::link(&mut bp, &rp);
+            scaffold::link(&mut rp, &bp);
+            vec![
+                (format!("{} BP", template.name), bp),
+                (format!("{} RP", template.name), rp),
+            ]
+        }
+    };
+    let packs = packs
+        .into_iter()
+        .map(|(folder, manifest)| (dir.join(folder), manifest))
+        .collect::<Vec<(PathBuf, Manifest)>>();
+    if let Some((folder, _)) = packs.iter().find(|(folder, _)| folder.exists()) {
+        return Err(anyhow!("{} already exists", folder.display()));
+    }
+    for (folder, manifest) in &packs {
+        write_pack(folder, manifest)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use std::io::{self, IsTerminal, Write};
+
+/// Whether the user can answer questions on the terminal.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Ask a yes/no question, anything but an explicit yes is a no.
+pub fn confirm<S: AsRef<str>>(question: S) -> Result<bool> {
+    print!("{} [y/N] ", question.as_ref());
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Show a numbered list and let the user pick some of the items by their numbers,
+/// returns the indices of the picked items, an empty answer picks nothing.
+pub fn select<S: AsRef<str>>(question: &str, items: &[S]) -> Result<Vec<usize>> {
+    for (i, item) in items.iter().enumerate() {
+        println!("  {}) {}", i + 1, item.as_ref());
+    }
+    loop {
+        print!("{question} (e.g. 1 or 1,3, leave empty to cancel): ");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        let picked = answer
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|i| !i.is_empty())
+            .map(|i| {
+                i.parse::<usize>()
+                    .ok()
+                    .filter(|i| (1..=items.len()).contains(i))
+                    .map(|i| i - 1)
+            })
+            .collect::<Option<Vec<_>>>();
+        match picked {
+            Some(mut picked) => {
+                picked.sort();
+                picked.dedup();
+                return Ok(picked);
+            }
+            None => println!("Please answer with numbers between 1 and {}", items.len()),
+        }
+    }
+}
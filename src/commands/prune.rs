@@ -0,0 +1,253 @@
+use anyhow::{Context, Result};
+use console::style;
+use prettytable::{Cell, Row, Table};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    print_scan_warnings, print_titled_table, prompt,
+    stats::{dir_usage, format_size},
+};
+use crate::addon::{
+    known_packs::{read_known_packs, KnownPack, KNOWN_PACKS_FILE},
+    manifest::PackateType,
+    scanner::{scan_server, scan_world, Scan, ScanError, ScannedPack},
+    world_packet_list::{read_packet_list, InUse},
+};
+
+/// A pack folder nothing refers to.
+struct Unused {
+    folder: PathBuf,
+    name: String,
+    version: String,
+    reason: String,
+    size: u64,
+}
+
+impl Unused {
+    fn new(folder: PathBuf, name: String, version: String, reason: String) -> Result<Unused> {
+        let size = dir_usage(&folder, false)?.total.disk;
+        Ok(Unused {
+            folder,
+            name,
+            version,
+            reason,
+            size,
+        })
+    }
+}
+
+/// The unused packs of a scan, a referenced pack installed in several folders is only kept once.
+fn find_unused<F>(scan: &Scan, referenced: F, reason: &str) -> Result<Vec<Unused>>
+where
+    F: Fn(&ScannedPack) -> bool,
+{
+    let mut out = Vec::new();
+    let mut kept: Vec<&ScannedPack> = Vec::new();
+    for pack in &scan.packs {
+        let reason = if !referenced(pack) {
+            reason.to_string()
+        } else if let Some(other) = kept.iter().find(|i| {
+            i.packate_type == pack.packate_type
+                && i.manifest.header.uuid == pack.manifest.header.uuid
+                && i.manifest.header.version == pack.manifest.header.version
+        }) {
+            format!("duplicate of {}", other.folder_name())
+        } else {
+            kept.push(pack);
+            continue;
+        };
+        out.push(Unused::new(
+            pack.folder.clone(),
+            pack.name.clone(),
+            pack.manifest.header.version.to_string(),
+            reason,
+        )?);
+    }
+    Ok(out)
+}
+
+/// The folders of a scan without a readable manifest, such as failed installs. Whether anything
+/// refers to them can not be told, so they are never pruned.
+fn unreadable_folders(scan: &Scan) -> Vec<PathBuf> {
+    scan.errors
+        .iter()
+        .filter_map(|err| match err {
+            ScanError::Io { path, .. } | ScanError::Manifest { path, .. }
+                if path.file_name().is_some_and(|i| i == "manifest.json") =>
+            {
+                path.parent().map(Path::to_path_buf)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn find_unused_in_world(
+    world: &Path,
+    unreadable: &mut Vec<PathBuf>,
+    lang: &str,
+) -> Result<Vec<Unused>> {
+    let mut scan = scan_world(world);
+    scan.localize(lang);
+    print_scan_warnings(&scan);
+    unreadable.extend(unreadable_folders(&scan));
+    if scan
+        .errors
+        .iter()
+        .any(|i| matches!(i, ScanError::PackList { .. }))
+    {
+        eprintln!(
+            "{} skipping {}, its pack lists could not be read",
+            style("warning:").yellow(),
+            world.display()
+        );
+        return Ok(Vec::new());
+    }
+    find_unused(
+        &scan,
+        |pack| pack.in_use.is_some(),
+        "not in the world pack list",
+    )
+}
+
+fn is_known(pack: &ScannedPack, known: &KnownPack, bds_root: &Path) -> bool {
+    match &known.path {
+        Some(path) => {
+            let path = path.replace('\\', "/");
+            pack.folder
+                .strip_prefix(bds_root)
+                .is_ok_and(|i| i == Path::new(path.trim_end_matches('/')))
+        }
+        None => {
+            known.uuid.as_ref() == Some(&pack.manifest.header.uuid)
+                && known
+                    .version
+                    .as_ref()
+                    .is_some_and(|i| *i == pack.manifest.header.version.to_string())
+        }
+    }
+}
+
+fn find_unused_in_server(
+    bds_root: &Path,
+    worlds: &[PathBuf],
+    unreadable: &mut Vec<PathBuf>,
+    lang: &str,
+) -> Result<Vec<Unused>> {
+    let known_path = bds_root.join(KNOWN_PACKS_FILE);
+    if !known_path.exists() {
+        eprintln!(
+            "{} skipping the server packs, {} does not exist yet",
+            style("warning:").yellow(),
+            KNOWN_PACKS_FILE
+        );
+        return Ok(Vec::new());
+    }
+    let known = read_known_packs(&known_path)?;
+    let mut lists: Vec<InUse> = Vec::new();
+    for world in worlds {
+        for packate_type in [PackateType::Resource, PackateType::Behavior] {
+            lists.extend(read_packet_list(world, packate_type).with_context(|| {
+                format!("Failed to check the pack lists of {}", world.display())
+            })?);
+        }
+    }
+
+    let mut scan = scan_server(bds_root);
+    scan.localize(lang);
+    print_scan_warnings(&scan);
+    unreadable.extend(unreadable_folders(&scan));
+    find_unused(
+        &scan,
+        |pack| {
+            lists.iter().any(|i| {
+                i.pack_id == pack.manifest.header.uuid && i.version == pack.manifest.header.version
+            }) || known.iter().any(|i| is_known(pack, i, bds_root))
+        },
+        "not used by any world or known to the server",
+    )
+}
+
+fn print_unused(unused: &[Unused]) {
+    let mut tab = Table::new();
+    tab.set_titles(Row::new(
+        ["folder", "name", "version", "reason", "size"]
+            .iter()
+            .map(|i| Cell::new(i).style_spec("Fm"))
+            .collect(),
+    ));
+    for i in unused {
+        tab.add_row(Row::new(vec![
+            Cell::new(&i.folder.display().to_string()),
+            Cell::new(&i.name).style_spec("Fb"),
+            Cell::new(&i.version).style_spec("Fc"),
+            Cell::new(&i.reason),
+            Cell::new(&format_size(i.size)).style_spec("Fy"),
+        ]));
+    }
+    print_titled_table(&mut tab);
+}
+
+/// Delete the pack folders of the worlds that are not in their pack lists, and with `bds_root`
+/// the server pack folders that no world uses and the server does not know.
+/// Nothing is deleted without a confirmation unless `yes` is given.
+pub fn prune(
+    worlds: &[PathBuf],
+    bds_root: Option<&Path>,
+    yes: bool,
+    interactive: bool,
+    lang: &str,
+) -> Result<()> {
+    let mut unused = Vec::new();
+    let mut unreadable = Vec::new();
+    for world in worlds {
+        unused.extend(find_unused_in_world(world, &mut unreadable, lang)?);
+    }
+    if let Some(bds_root) = bds_root {
+        unused.extend(find_unused_in_server(
+            bds_root,
+            worlds,
+            &mut unreadable,
+            lang,
+        )?);
+    }
+    for folder in &unreadable {
+        println!(
+            "{} {} is unreadable, not pruned",
+            style("note:").cyan(),
+            folder.display()
+        );
+    }
+    if unused.is_empty() {
+        println!("There are no unused pack folders");
+        return Ok(());
+    }
+
+    print_unused(&unused);
+    let total = format_size(unused.iter().map(|i| i.size).sum());
+    if !yes {
+        if !interactive {
+            println!(
+                "{} {} folder(s) would free {total}, use --yes to delete them",
+                style("note:").cyan(),
+                unused.len()
+            );
+            return Ok(());
+        }
+        if !prompt::confirm(format!(
+            "Delete {} folder(s) to free {total}?",
+            unused.len()
+        ))? {
+            return Ok(());
+        }
+    }
+    for i in &unused {
+        fs::remove_dir_all(&i.folder)
+            .with_context(|| format!("Failed to remove {}", i.folder.display()))?;
+    }
+    println!("Deleted {} folder(s), freed {total}", unused.len());
+    Ok(())
+}
@@ -1,143 +1,416 @@
 use anyhow::{anyhow, Context, Result};
+use console::style;
+use serde::Serialize;
 use std::{
-    fs::{self, DirEntry},
-    path::Path,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
 };
 
+use super::{print_scan_warnings, prompt};
 use crate::addon::{
-    self,
-    manifest::{Manifest, PackateType},
-    world_packet_list::{parse_in_use_packet_list, to_packet_list_string, InUse},
+    json_style::JsonStyle,
+    known_packs::{read_known_packs, KNOWN_PACKS_FILE},
+    manifest::PackateType,
+    scanner::{scan_world, ScannedPack},
+    selector::Selector,
+    trash,
+    world_packet_list::{read_history, read_packet_list, write_history, write_packet_list, InUse},
 };
 
-fn get_list<P: AsRef<Path>>(
-    packate_type: PackateType,
-    target: P,
-) -> Result<Vec<(String, InUse, DirEntry)>> {
-    let list = parse_in_use_packet_list(fs::read_to_string(
-        target.as_ref().join(packate_type.get_list_file_string()),
-    )?)?;
-    let mut out_list = Vec::<(String, InUse, DirEntry)>::new();
-    for i in target
-        .as_ref()
-        .join(packate_type.get_path_name())
-        .read_dir()?
-        .flatten()
-    {
-        if let Ok(typ) = i.file_type() {
-            if typ.is_dir() {
-                let data = Manifest::new(fs::read_to_string(i.path().join("manifest.json"))?)?;
-                if let Some(inuse) = list
-                    .iter()
-                    .find(|i| i.pack_id == data.header.uuid && i.version == data.header.version)
-                {
-                    out_list.push((data.header.name, inuse.clone(), i));
-                }
-            }
-        }
-    }
-    Ok(out_list)
+pub struct RemoveOptions {
+    /// Uninstall both behavior and resource packs with the same name.
+    pub all: bool,
+    /// Also uninstall the packs that depend on the removed ones.
+    pub cascade: bool,
+    /// Uninstall even if other packs depend on the removed ones.
+    pub force: bool,
+    /// Delete the packs instead of moving them to the trash, and also remove their config files,
+    /// `valid_known_packs.json` entries and history records.
+    pub purge: bool,
+    /// Uninstall every installed version of the pack.
+    pub all_versions: bool,
+    /// Uninstall every installed version of the pack except the latest one.
+    pub keep_latest: bool,
+    /// Ask the user instead of failing when the choice is ambiguous.
+    pub interactive: bool,
+    pub lang: String,
 }
 
 fn remove_form_list_file<P: AsRef<Path>>(
     target: P,
-    info: InUse,
+    info: &InUse,
     packate_type: PackateType,
 ) -> Result<()> {
     let target = target.as_ref();
-    let packet_list = if target.join(packate_type.get_list_file_string()).exists() {
-        addon::world_packet_list::parse_in_use_packet_list(
-            fs::read_to_string(target.join(packate_type.get_list_file_string())).with_context(
-                || format!("Failed to read {}", packate_type.get_list_file_string()),
-            )?,
-        )
-        .with_context(|| {
-            format!(
-                "Failed to properly parse an existing {}",
-                packate_type.get_list_file_string()
-            )
-        })?
-    } else {
+    if !target.join(packate_type.get_list_file_string()).exists() {
         return Err(anyhow!(
             "{} not exists",
             packate_type.get_list_file_string()
         ));
-    };
+    }
+    let packet_list = read_packet_list(target, packate_type)?;
+    write_packet_list(
+        target,
+        packate_type,
+        packet_list
+            .into_iter()
+            .filter(|i| i != info)
+            .collect::<Vec<_>>(),
+    )
+}
 
-    fs::write(
-        target.join(packate_type.get_list_file_string()),
-        to_packet_list_string(
-            packet_list
-                .into_iter()
-                .filter(|i| !(*i == info))
-                .collect::<Vec<_>>(),
-        )?,
-    )?;
-    Ok(())
+/// Files outside of the pack folder and the pack list that still refer to a removed pack.
+enum Leftover {
+    Config(PathBuf),
+    KnownPacks(PathBuf),
+    History(PathBuf),
 }
 
-pub fn remove<S: AsRef<str>, P: AsRef<Path>>(name: S, all: bool, target: P) -> Result<()> {
-    let bp_list = get_list(PackateType::Behavior, &target)
-        .with_context(|| "When reading the behavior packs")?;
-    let rp_list = get_list(PackateType::Resource, &target)
-        .with_context(|| "When reading the resource packs")?;
-    // by uuid
-    if let Some(res) = bp_list
-        .as_slice()
-        .into_iter()
-        .find(|i| i.1.pack_id == name.as_ref())
-    {
-        fs::remove_dir_all(res.2.path())?;
-        remove_form_list_file(&target, res.1.clone(), PackateType::Behavior)?;
-        println!("Package {} was successfully removed", res.0);
-        return Ok(());
-    } else if let Some(res) = rp_list
-        .as_slice()
-        .into_iter()
-        .find(|i| i.1.pack_id == name.as_ref())
-    {
-        fs::remove_dir_all(res.2.path())?;
-        remove_form_list_file(&target, res.1.clone(), PackateType::Resource)?;
-        println!("Package {} was successfully removed", res.0);
-        return Ok(());
+impl Display for Leftover {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Leftover::Config(path) => write!(f, "config folder {}", path.display()),
+            Leftover::KnownPacks(path) | Leftover::History(path) => {
+                write!(f, "entry in {}", path.display())
+            }
+        }
     }
-    // by name
-    let bp_res = bp_list
-        .into_iter()
-        .filter(|i| i.0 == name.as_ref())
-        .collect::<Vec<_>>();
-    let rp_res = rp_list
-        .into_iter()
-        .filter(|i| i.0 == name.as_ref())
+}
+
+fn is_same_pack(pack: &ScannedPack, uuid: Option<&String>, version: Option<String>) -> bool {
+    uuid == Some(&pack.manifest.header.uuid)
+        && version.is_none_or(|i| i == pack.manifest.header.version.to_string())
+}
+
+/// Write a file again in the formatting and key order it already has.
+fn rewrite<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let style = JsonStyle::of(
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?,
+    );
+    fs::write(path, style.to_string(value)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+struct Remover<'a> {
+    target: &'a Path,
+    bds_root: Option<&'a Path>,
+    purge: bool,
+}
+
+impl Remover<'_> {
+    /// `shared` is whether another version of the pack stays installed, which still uses the config.
+    fn find_leftovers(&self, pack: &ScannedPack, shared: bool) -> Result<Vec<Leftover>> {
+        let mut out = Vec::new();
+        if let Some(bds_root) = self.bds_root {
+            let config = bds_root.join("config").join(&pack.manifest.header.uuid);
+            if config.exists() && !shared {
+                out.push(Leftover::Config(config));
+            }
+            let known = bds_root.join(KNOWN_PACKS_FILE);
+            if known.exists()
+                && read_known_packs(&known)?
+                    .into_iter()
+                    .any(|i| is_same_pack(pack, i.uuid.as_ref(), i.version))
+            {
+                out.push(Leftover::KnownPacks(known));
+            }
+        }
+        if read_history(self.target, pack.packate_type)?
+            .forget(&pack.manifest.header.uuid, &pack.manifest.header.version)
+        {
+            out.push(Leftover::History(
+                self.target
+                    .join(pack.packate_type.get_history_file_string()),
+            ));
+        }
+        Ok(out)
+    }
+
+    fn purge(&self, pack: &ScannedPack, leftover: &Leftover) -> Result<()> {
+        match leftover {
+            Leftover::Config(path) => fs::remove_dir_all(path)?,
+            Leftover::KnownPacks(path) => {
+                let list = read_known_packs(path)?
+                    .into_iter()
+                    .filter(|i| !is_same_pack(pack, i.uuid.as_ref(), i.version.clone()))
+                    .collect::<Vec<_>>();
+                rewrite(path, &list)?
+            }
+            Leftover::History(_) => {
+                let mut history = read_history(self.target, pack.packate_type)?;
+                history.forget(&pack.manifest.header.uuid, &pack.manifest.header.version);
+                write_history(self.target, pack.packate_type, &history)?
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&self, pack: &ScannedPack, shared: bool) -> Result<()> {
+        if self.purge {
+            fs::remove_dir_all(&pack.folder)
+                .with_context(|| format!("Failed to remove {}", pack.folder.display()))?;
+            println!("Package {} was successfully removed", pack.name);
+        } else {
+            let priority = match &pack.in_use {
+                Some(in_use) => read_packet_list(self.target, pack.packate_type)?
+                    .iter()
+                    .position(|i| i == in_use),
+                None => None,
+            };
+            let entry = trash::put(self.target, pack, priority)?;
+            println!(
+                "Package {} was moved to the trash, restore it with `trash restore {}`",
+                pack.name, entry.id
+            );
+        }
+        if let Some(in_use) = &pack.in_use {
+            remove_form_list_file(self.target, in_use, pack.packate_type)?;
+        }
+
+        let leftovers = self.find_leftovers(pack, shared)?;
+        if self.purge {
+            for leftover in leftovers {
+                self.purge(pack, &leftover)
+                    .with_context(|| format!("Failed to purge the {leftover}"))?;
+                println!("Purged the {leftover}");
+            }
+        } else if !leftovers.is_empty() {
+            println!(
+                "{} {} leftover(s) of {} remain, use --purge to remove them:",
+                style("note:").cyan(),
+                leftovers.len(),
+                pack.name
+            );
+            for leftover in leftovers {
+                println!("  {leftover}");
+            }
+        }
+        Ok(())
+    }
+}
+
+fn depends_on(pack: &ScannedPack, dependency: &ScannedPack) -> bool {
+    pack.manifest.dependencies.as_ref().is_some_and(|deps| {
+        deps.iter()
+            .any(|i| i.uuid.as_ref() == Some(&dependency.manifest.header.uuid))
+    })
+}
+
+/// Whether another version of the pack at `index` stays installed when `selected` are removed.
+fn has_other_version(list: &[ScannedPack], selected: &[usize], index: usize) -> bool {
+    (0..list.len()).any(|i| {
+        !selected.contains(&i) && list[i].manifest.header.uuid == list[index].manifest.header.uuid
+    })
+}
+
+/// Indices of the packs outside of `selected` that depend on one of the selected packs,
+/// ignoring the selected packs another version of which stays installed.
+fn get_dependents(list: &[ScannedPack], selected: &[usize]) -> Vec<usize> {
+    (0..list.len())
+        .filter(|i| !selected.contains(i))
+        .filter(|i| {
+            selected
+                .iter()
+                .filter(|s| !has_other_version(list, selected, **s))
+                .any(|s| depends_on(&list[*i], &list[*s]))
+        })
+        .collect()
+}
+
+/// Indices of the dependencies of the removed packs that no remaining pack depends on.
+fn get_unneeded(list: &[ScannedPack], removed: &[usize]) -> Vec<usize> {
+    (0..list.len())
+        .filter(|i| !removed.contains(i))
+        .filter(|i| removed.iter().any(|r| depends_on(&list[*r], &list[*i])))
+        .filter(|i| {
+            !(0..list.len())
+                .filter(|o| !removed.contains(o))
+                .any(|o| depends_on(&list[o], &list[*i]))
+        })
+        .collect()
+}
+
+fn names(list: &[ScannedPack], indices: &[usize]) -> String {
+    indices
+        .iter()
+        .map(|i| format!("{} [{}]", list[*i].name, list[*i].manifest.header.version))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The distinct uuids of the packs at `indices`.
+fn uuids<'a>(list: &'a [ScannedPack], indices: &[usize]) -> Vec<&'a String> {
+    let mut uuids = indices
+        .iter()
+        .map(|i| &list[*i].manifest.header.uuid)
         .collect::<Vec<_>>();
-    if bp_res.len() > 1 || rp_res.len() > 1 {
-        return Err(anyhow!("To find multiple matches, please use uuid"));
+    uuids.sort();
+    uuids.dedup();
+    uuids
+}
+
+/// Why the matched packs can not be removed without asking which ones are meant.
+fn ambiguity(
+    list: &[ScannedPack],
+    res: &[usize],
+    name: &str,
+    options: &RemoveOptions,
+) -> Option<String> {
+    if !options.all_versions && !options.keep_latest {
+        for uuid in uuids(list, res) {
+            let versions = res
+                .iter()
+                .filter(|i| &list[**i].manifest.header.uuid == uuid)
+                .map(|i| list[*i].manifest.header.version.to_string())
+                .collect::<Vec<_>>();
+            if versions.len() > 1 {
+                return Some(format!(
+                    "Several versions of {} are installed ({}), please use {}@<version>, --all-versions or --keep-latest",
+                    list[res[0]].name,
+                    versions.join(", "),
+                    name
+                ));
+            }
+        }
     }
-    if bp_res.len() == 1 && rp_res.len() == 1 {
-        if !all {
-            return Err(anyhow!(
-                "There are behavior packages and resource packages with the same name,\
+
+    let count = |packate_type: PackateType| {
+        let res = res
+            .iter()
+            .copied()
+            .filter(|i| list[*i].packate_type == packate_type)
+            .collect::<Vec<_>>();
+        uuids(list, &res).len()
+    };
+    let (bp_count, rp_count) = (count(PackateType::Behavior), count(PackateType::Resource));
+    if bp_count > 1 || rp_count > 1 {
+        return Some("To find multiple matches, please use uuid".to_string());
+    }
+    if bp_count == 1 && rp_count == 1 && !options.all {
+        return Some(
+            "There are behavior packages and resource packages with the same name,\
              if you need to uninstall them separately, please use uuid."
+                .to_string(),
+        );
+    }
+    None
+}
+
+fn find(list: &[ScannedPack], selector: &Selector, options: &RemoveOptions) -> Result<Vec<usize>> {
+    let mut res = selector.select(list)?;
+
+    if options.keep_latest {
+        for uuid in uuids(list, &res) {
+            let latest = res
+                .iter()
+                .copied()
+                .filter(|i| &list[*i].manifest.header.uuid == uuid)
+                .max_by(|a, b| {
+                    list[*a]
+                        .manifest
+                        .header
+                        .version
+                        .cmp(&list[*b].manifest.header.version)
+                });
+            res.retain(|i| Some(*i) != latest);
+        }
+        if res.is_empty() {
+            return Err(anyhow!(
+                "Only the latest version is installed, nothing to remove"
             ));
         }
-        fs::remove_dir_all(bp_res[0].2.path())?;
-        remove_form_list_file(&target, bp_res[0].1.clone(), PackateType::Behavior)?;
-        fs::remove_dir_all(rp_res[0].2.path())?;
-        remove_form_list_file(&target, rp_res[0].1.clone(), PackateType::Resource)?;
+    }
 
-        println!("Package {} was successfully removed", bp_res[0].0);
-        return Ok(());
+    if let Some(message) = ambiguity(list, &res, &selector.query, options) {
+        if !options.interactive {
+            return Err(anyhow!(message));
+        }
+        println!("Several packages match {selector}:");
+        let choices = res
+            .iter()
+            .map(|i| {
+                let pack = &list[*i];
+                format!(
+                    "{} {} {} {} {}",
+                    style(&pack.name).bold(),
+                    style(pack.packate_type.get_short_name()).cyan(),
+                    style(pack.manifest.header.version.to_string()).cyan(),
+                    style(&pack.manifest.header.uuid).yellow(),
+                    pack.folder.display()
+                )
+            })
+            .collect::<Vec<_>>();
+        let selected = prompt::select("Packages to remove", &choices)?;
+        if selected.is_empty() {
+            return Err(anyhow!("No package selected"));
+        }
+        res = selected.into_iter().map(|i| res[i]).collect();
     }
-    let (res, packate_type) = if bp_res.len() == 1 {
-        (&bp_res[0], PackateType::Behavior)
-    } else if rp_res.len() == 1 {
-        (&rp_res[0], PackateType::Resource)
-    } else {
-        return Err(anyhow!("No matching packages found"));
+    Ok(res)
+}
+
+pub fn remove<S: AsRef<str>, P: AsRef<Path>>(
+    name: S,
+    target: P,
+    bds_root: Option<&Path>,
+    options: RemoveOptions,
+) -> Result<()> {
+    let remover = Remover {
+        target: target.as_ref(),
+        bds_root,
+        purge: options.purge,
     };
-    fs::remove_dir_all(res.2.path())?;
-    remove_form_list_file(&target, res.1.clone(), packate_type)?;
-    println!("Package {} was successfully removed", res.0);
+    let mut scan = scan_world(&target);
+    scan.localize(&options.lang);
+    print_scan_warnings(&scan);
+    let list = scan.into_enabled();
 
+    let mut removing = find(&list, &Selector::parse(name), &options)?;
+    loop {
+        let dependents = get_dependents(&list, &removing);
+        if dependents.is_empty() {
+            break;
+        }
+        if options.force {
+            println!(
+                "{} {} still required by {}",
+                style("warning:").yellow(),
+                names(&list, &removing),
+                names(&list, &dependents)
+            );
+            break;
+        }
+        if !options.cascade {
+            return Err(anyhow!(
+                "{} is required by {}, use --cascade to remove them as well or --force to remove anyway",
+                names(&list, &removing),
+                names(&list, &dependents)
+            ));
+        }
+        removing.extend(dependents);
+    }
+    for i in &removing {
+        remover.remove(&list[*i], has_other_version(&list, &removing, *i))?;
+    }
+
+    loop {
+        let unneeded = get_unneeded(&list, &removing);
+        if unneeded.is_empty() {
+            break;
+        }
+        let message = format!("{} no longer required by any pack", names(&list, &unneeded));
+        if !options.interactive {
+            println!("{} {message}", style("note:").cyan());
+            break;
+        }
+        if !prompt::confirm(format!("{message}, remove them too?"))? {
+            break;
+        }
+        removing.extend(unneeded.iter().copied());
+        for i in &unneeded {
+            remover.remove(&list[*i], has_other_version(&list, &removing, *i))?;
+        }
+    }
     Ok(())
 }
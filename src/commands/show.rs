@@ -0,0 +1,98 @@
+use anyhow::Result;
+use console::style;
+use prettytable::table;
+use std::path::Path;
+
+use super::print_kv_table;
+use crate::addon::{
+    manifest::format::{self, FormatVersion},
+    scanner::{scan_world, ScannedPack},
+    selector::Selector,
+};
+
+fn format_cell(format_version: FormatVersion) -> String {
+    if format_version.is_outdated() {
+        format!(
+            "{format_version} (outdated, the latest is {})",
+            FormatVersion::LATEST
+        )
+    } else {
+        format_version.to_string()
+    }
+}
+
+fn print_pack(pack: &ScannedPack) {
+    let manifest = &pack.manifest;
+    let header = &manifest.header;
+    let join = |list: Vec<String>| {
+        if list.is_empty() {
+            "-".to_string()
+        } else {
+            list.join(", ")
+        }
+    };
+    let mut tab = table!(
+        [Fm->"name", Fb->pack.name],
+        [Fm->"version", Fc->header.version],
+        [Fm->"uuid", Fy->header.uuid],
+        [Fm->"type", Fc->pack.packate_type.get_path_name().replace("_", " ")],
+        [Fm->"format", Fc->format_cell(manifest.format_version())],
+        [
+            Fm->"min engine",
+            header
+                .min_engine_version
+                .as_ref()
+                .map(|i| i.to_string())
+                .unwrap_or("-".to_string())
+        ],
+        [
+            Fm->"modules",
+            join(
+                manifest
+                    .modules
+                    .iter()
+                    .map(|i| i.module_type.name().to_string())
+                    .collect()
+            )
+        ],
+        [
+            Fm->"authors",
+            join(
+                manifest
+                    .metadata
+                    .as_ref()
+                    .and_then(|i| i.authors.as_ref())
+                    .map(|i| i.as_slice().to_vec())
+                    .unwrap_or_default()
+            )
+        ],
+        [Fm->"status", if pack.is_enabled() { "enabled" } else { "disabled" }],
+        [Fm->"folder", pack.folder.display()],
+        [Fm->"description", pack.description.clone().unwrap_or_default()]
+    );
+    print_kv_table(&mut tab);
+    for diagnostic in &pack.diagnostics {
+        println!("{} {diagnostic}", style("warning:").yellow());
+    }
+    for issue in format::check(manifest) {
+        println!("{} {issue}", style("warning:").yellow());
+    }
+}
+
+/// Show everything known about the installed packs matching the selector, in use or not,
+/// along with the problems found in them.
+pub fn show<S: AsRef<str>, P: AsRef<Path>>(name: S, target: P, lang: &str) -> Result<()> {
+    let mut scan = scan_world(target);
+    scan.localize(lang);
+    for (index, i) in Selector::parse(name)
+        .select(&scan.packs)?
+        .into_iter()
+        .enumerate()
+    {
+        if index > 0 {
+            println!();
+        }
+        print_pack(&scan.packs[i]);
+    }
+    Ok(())
+}
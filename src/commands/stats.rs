@@ -0,0 +1,290 @@
+use super::{print_scan_warnings, print_titled_table};
+use crate::addon::{manifest::PackateType, scanner::scan_world_packs};
+use anyhow::{Context, Result};
+use flate2::{write::DeflateEncoder, Compression};
+use prettytable::{Cell, Row, Table};
+use std::{
+    fs, io,
+    ops::AddAssign,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileKind {
+    Texture,
+    Sound,
+    Model,
+    Script,
+    Json,
+    Other,
+}
+
+impl FileKind {
+    const ALL: [FileKind; 6] = [
+        FileKind::Texture,
+        FileKind::Sound,
+        FileKind::Model,
+        FileKind::Script,
+        FileKind::Json,
+        FileKind::Other,
+    ];
+
+    fn title(&self) -> &str {
+        match self {
+            FileKind::Texture => "textures",
+            FileKind::Sound => "sounds",
+            FileKind::Model => "models",
+            FileKind::Script => "scripts",
+            FileKind::Json => "json",
+            FileKind::Other => "other",
+        }
+    }
+
+    /// Guess the kind of a file from its path relative to the pack root.
+    pub fn of<P: AsRef<Path>>(path: P) -> FileKind {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .map(|i| i.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let extension = name.rsplit('.').next().unwrap_or_default();
+        match extension {
+            "png" | "tga" | "jpg" | "jpeg" => FileKind::Texture,
+            "ogg" | "wav" | "fsb" | "mp3" => FileKind::Sound,
+            "js" | "mjs" | "ts" => FileKind::Script,
+            "json" if name.ends_with(".geo.json") || path.starts_with("models") => FileKind::Model,
+            "json" => FileKind::Json,
+            _ => FileKind::Other,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Usage {
+    pub files: u64,
+    /// Sum of the file lengths.
+    pub size: u64,
+    /// Space allocated on the disk, which is at least `size` on most file systems.
+    pub disk: u64,
+    /// Estimated size of the files after deflate compression, as sent to clients or packed into an archive.
+    pub compressed: u64,
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, rhs: Self) {
+        self.files += rhs.files;
+        self.size += rhs.size;
+        self.disk += rhs.disk;
+        self.compressed += rhs.compressed;
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct PackUsage {
+    pub total: Usage,
+    pub kinds: [Usage; FileKind::ALL.len()],
+}
+
+impl AddAssign for PackUsage {
+    fn add_assign(&mut self, rhs: Self) {
+        self.total += rhs.total;
+        for (i, o) in self.kinds.iter_mut().zip(rhs.kinds) {
+            *i += o;
+        }
+    }
+}
+
+#[cfg(unix)]
+fn disk_usage(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn disk_usage(metadata: &fs::Metadata) -> u64 {
+    // assume the common 4 KiB cluster size
+    metadata.len().div_ceil(4096) * 4096
+}
+
+struct CountingSink(u64);
+
+impl io::Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn compressed_size(path: &Path) -> Result<u64> {
+    let mut encoder = DeflateEncoder::new(CountingSink(0), Compression::default());
+    io::copy(&mut fs::File::open(path)?, &mut encoder)?;
+    Ok(encoder.finish()?.0)
+}
+
+fn walk(root: &Path, dir: &Path, compress: bool, usage: &mut PackUsage) -> Result<()> {
+    for entry in dir
+        .read_dir()
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+    {
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            walk(root, &path, compress, usage)?;
+            continue;
+        }
+        let file = Usage {
+            files: 1,
+            size: metadata.len(),
+            disk: disk_usage(&metadata),
+            compressed: if compress {
+                compressed_size(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?
+            } else {
+                0
+            },
+        };
+        let kind = FileKind::of(path.strip_prefix(root).unwrap_or(&path));
+        usage.total += file;
+        usage.kinds[FileKind::ALL.iter().position(|i| *i == kind).unwrap()] += file;
+    }
+    Ok(())
+}
+
+/// Add up the files of a directory, `compress` enables the (slow) compressed size estimation.
+pub fn dir_usage<P: AsRef<Path>>(dir: P, compress: bool) -> Result<PackUsage> {
+    let mut usage = PackUsage::default();
+    walk(dir.as_ref(), dir.as_ref(), compress, &mut usage)?;
+    Ok(usage)
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn usage_cells(usage: &PackUsage) -> Vec<Cell> {
+    let mut cells = vec![
+        Cell::new(&usage.total.files.to_string()),
+        Cell::new(&format_size(usage.total.size)).style_spec("Fc"),
+        Cell::new(&format_size(usage.total.disk)),
+        Cell::new(&format_size(usage.total.compressed)).style_spec("Fy"),
+    ];
+    cells.extend(usage.kinds.iter().map(|i| {
+        if i.files == 0 {
+            Cell::new("-")
+        } else {
+            Cell::new(&format_size(i.size))
+        }
+    }));
+    cells
+}
+
+fn usage_titles(first: &[&str]) -> Row {
+    Row::new(
+        first
+            .iter()
+            .copied()
+            .chain(["files", "size", "on disk", "compressed"])
+            .chain(FileKind::ALL.iter().map(|i| i.title()))
+            .map(|i| Cell::new(i).style_spec("Fm"))
+            .collect(),
+    )
+}
+
+/// Print the usage of every pack installed in a world and return the world total.
+pub fn stats<P: AsRef<Path>>(
+    target: P,
+    resource: bool,
+    behavior: bool,
+    lang: &str,
+) -> Result<PackUsage> {
+    let (res, beh) = match (resource, behavior) {
+        (false, false) => (true, true),
+        a => a,
+    };
+    let target = target.as_ref();
+    let mut tab = Table::new();
+    tab.set_titles(usage_titles(&["name", "type", "status"]));
+    let mut world_total = PackUsage::default();
+    let mut type_totals = Vec::new();
+    for (packate_type, _) in [(PackateType::Resource, res), (PackateType::Behavior, beh)]
+        .into_iter()
+        .filter(|i| i.1)
+    {
+        let mut scan = scan_world_packs(target, packate_type);
+        scan.localize(lang);
+        print_scan_warnings(&scan);
+        let mut type_total = PackUsage::default();
+        for pack in &scan.packs {
+            let usage = dir_usage(&pack.folder, true)?;
+            let mut row = vec![
+                Cell::new(&pack.name).style_spec("Fb"),
+                Cell::new(pack.packate_type.get_short_name()).style_spec("Fc"),
+                Cell::new(if pack.is_enabled() {
+                    "enabled"
+                } else {
+                    "disabled"
+                }),
+            ];
+            row.extend(usage_cells(&usage));
+            tab.add_row(Row::new(row));
+            type_total += usage;
+        }
+        world_total += type_total;
+        type_totals.push((packate_type, type_total));
+    }
+    print_titled_table(&mut tab);
+
+    println!();
+    let mut tab = Table::new();
+    tab.set_titles(usage_titles(&["total"]));
+    for (packate_type, usage) in type_totals {
+        let mut row = vec![Cell::new(packate_type.get_path_name()).style_spec("Fc")];
+        row.extend(usage_cells(&usage));
+        tab.add_row(Row::new(row));
+    }
+    let mut row = vec![Cell::new("world").style_spec("Fb")];
+    row.extend(usage_cells(&world_total));
+    tab.add_row(Row::new(row));
+    print_titled_table(&mut tab);
+    Ok(world_total)
+}
+
+/// Print the usage of every world of a bds, followed by the totals of each world.
+pub fn stats_all_worlds(
+    worlds: &[PathBuf],
+    resource: bool,
+    behavior: bool,
+    lang: &str,
+) -> Result<()> {
+    let mut totals = Vec::new();
+    for world in worlds {
+        let name = world.file_name().unwrap_or_default().to_string_lossy();
+        println!("{}", console::style(&name).bold());
+        totals.push((name.to_string(), stats(world, resource, behavior, lang)?));
+        println!();
+    }
+    let mut tab = Table::new();
+    tab.set_titles(usage_titles(&["world"]));
+    for (name, usage) in totals {
+        let mut row = vec![Cell::new(&name).style_spec("Fb")];
+        row.extend(usage_cells(&usage));
+        tab.add_row(Row::new(row));
+    }
+    print_titled_table(&mut tab);
+    Ok(())
+}
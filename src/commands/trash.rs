@@ -0,0 +1,162 @@
+use anyhow::{anyhow, Context, Result};
+use prettytable::{Cell, Row, Table};
+use std::{fs, path::Path, time::Duration};
+
+use super::{install::get_available_folder, print_titled_table};
+use crate::addon::{
+    scanner::scan_world_packs,
+    trash::{self, TrashEntry},
+    world_packet_list::{
+        packet_list_style, read_history, read_packet_list, write_history,
+        write_packet_list_in_style, InUse,
+    },
+};
+
+/// Parse an age such as `30m`, `12h`, `7d` or `2w`, a number without a unit is in days.
+pub fn parse_age(str: &str) -> Result<Duration, String> {
+    let str = str.trim();
+    let (number, unit) = str.split_at(str.find(|c: char| !c.is_ascii_digit()).unwrap_or(str.len()));
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| format!("invalid age `{str}`, expected something like 7d or 12h"))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "unknown unit `{unit}`, expected one of s, m, h, d, w"
+            ))
+        }
+    };
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("age `{str}` is too large"))
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        3600..86400 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+pub fn list<P: AsRef<Path>>(target: P) -> Result<()> {
+    let entries = trash::list(&target)?;
+    if entries.is_empty() {
+        println!("The trash is empty");
+        return Ok(());
+    }
+    let mut tab = Table::new();
+    tab.set_titles(Row::new(
+        ["id", "name", "type", "version", "uuid", "removed", "in use"]
+            .iter()
+            .map(|i| Cell::new(i).style_spec("Fm"))
+            .collect(),
+    ));
+    for entry in &entries {
+        tab.add_row(Row::new(vec![
+            Cell::new(&entry.id.to_string()),
+            Cell::new(&entry.name).style_spec("Fb"),
+            Cell::new(entry.packate_type.get_short_name()).style_spec("Fc"),
+            Cell::new(&entry.version.to_string()).style_spec("Fc"),
+            Cell::new(&entry.uuid).style_spec("Fy"),
+            Cell::new(&format_age(entry.age())),
+            Cell::new(if entry.in_use.is_some() { "yes" } else { "no" }),
+        ]));
+    }
+    print_titled_table(&mut tab);
+    Ok(())
+}
+
+/// Move a pack back to the world and put it back to its place in the world pack list.
+pub fn restore<P: AsRef<Path>>(target: P, id: u64) -> Result<()> {
+    let target = target.as_ref();
+    let entry = trash::get(target, id)?;
+    let installed = scan_world_packs(target, entry.packate_type);
+    if installed
+        .packs
+        .iter()
+        .any(|i| i.manifest.header.uuid == entry.uuid && i.manifest.header.version == entry.version)
+    {
+        return Err(anyhow!(
+            "{} [{}] is already installed in the world",
+            entry.name,
+            entry.version
+        ));
+    }
+
+    let packs_dir = target.join(entry.packate_type.get_path_name());
+    fs::create_dir_all(&packs_dir)
+        .with_context(|| format!("Failed to create {}", packs_dir.display()))?;
+    let folder = get_available_folder(&entry.folder, &packs_dir);
+    fs::rename(entry.pack_path(target), &folder).with_context(|| {
+        format!(
+            "Failed to move {} to {}",
+            entry.pack_path(target).display(),
+            folder.display()
+        )
+    })?;
+    restore_in_use(target, &entry)?;
+    restore_history(target, &entry)?;
+    trash::delete(target, &entry)?;
+    println!(
+        "Package {} was restored to {}",
+        entry.name,
+        folder.display()
+    );
+    Ok(())
+}
+
+fn restore_in_use(target: &Path, entry: &TrashEntry) -> Result<()> {
+    let Some(raw) = &entry.in_use else {
+        return Ok(());
+    };
+    let in_use = serde_json::from_value::<InUse>(raw.clone().into()).with_context(|| {
+        format!(
+            "Failed to read the pack list entry of trash entry {}",
+            entry.id
+        )
+    })?;
+    let mut list = read_packet_list(target, entry.packate_type)?;
+    if !list.contains(&in_use) {
+        // a list emptied since the removal no longer shows how it was written
+        let style = match &entry.list_style {
+            Some(style) if list.is_empty() => style.clone(),
+            _ => packet_list_style(target, entry.packate_type),
+        };
+        let index = entry.priority.unwrap_or(list.len()).min(list.len());
+        list.insert(index, in_use);
+        write_packet_list_in_style(target, entry.packate_type, list, &style)?;
+    }
+    Ok(())
+}
+
+/// Put back the history records the pack had when it was removed, which are only gone when the
+/// pack was purged since.
+fn restore_history(target: &Path, entry: &TrashEntry) -> Result<()> {
+    let mut history = read_history(target, entry.packate_type)?;
+    if history.restore(&entry.history) {
+        write_history(target, entry.packate_type, &history)?;
+    }
+    Ok(())
+}
+
+/// Permanently delete the packs in the trash, only those removed before `older_than` when given.
+pub fn empty<P: AsRef<Path>>(target: P, older_than: Option<Duration>) -> Result<()> {
+    let target = target.as_ref();
+    let entries = trash::list(target)?
+        .into_iter()
+        .filter(|i| older_than.is_none_or(|age| i.age() >= age.as_secs()))
+        .collect::<Vec<_>>();
+    for entry in &entries {
+        trash::delete(target, entry)?;
+    }
+    println!("Deleted {} package(s) from the trash", entries.len());
+    Ok(())
+}
@@ -2,22 +2,109 @@ pub mod addon;
 pub mod commands;
 
 use anyhow::{anyhow, Context, Result};
-use std::path::Path;
+use std::{
+    fmt::Display,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
-pub fn is_bds_exist<P: AsRef<Path>>(path: P) -> Result<bool> {
-    let extries = path
-        .as_ref()
-        .read_dir()
-        .with_context(|| "Unable to read working directory")?;
+/// How the bedrock server in a folder is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerLayout {
+    /// The `bedrock_server` binary of the linux server.
+    Linux,
+    /// The `bedrock_server.exe` of the windows server.
+    Windows,
+    /// A windows server started by LeviLamina, which adds `bedrock_server_mod.exe`.
+    LeviLamina,
+    /// A server run by Endstone, which keeps the bds in a `bedrock_server` folder.
+    Endstone,
+    /// A folder with the worlds and the files of a bds, but no known binary.
+    Unknown,
+}
+
+impl Display for ServerLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ServerLayout::Linux => "linux bds",
+            ServerLayout::Windows => "windows bds",
+            ServerLayout::LeviLamina => "LeviLamina",
+            ServerLayout::Endstone => "Endstone",
+            ServerLayout::Unknown => "unknown",
+        })
+    }
+}
+
+/// A bedrock server found in a folder.
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub layout: ServerLayout,
+    /// The folder of the bds, which holds the `worlds` folder.
+    pub root: PathBuf,
+    pub binary: Option<PathBuf>,
+}
+
+const ENDSTONE_SERVER_DIR: &str = "bedrock_server";
 
-    for entry in extries.flatten() {
-        if let Some(str) = entry.file_name().to_str() {
-            if str.starts_with("bedrock_server.exe") {
-                return Ok(true);
-            }
+fn is_elf<P: AsRef<Path>>(path: P) -> bool {
+    let mut magic = [0; 4];
+    fs::File::open(path)
+        .and_then(|mut i| i.read_exact(&mut magic))
+        .is_ok_and(|_| &magic == b"\x7fELF")
+}
+
+/// The server binary in the bds folder and the layout it tells.
+fn find_binary(path: &Path) -> Option<(ServerLayout, PathBuf)> {
+    [
+        ("bedrock_server_mod.exe", ServerLayout::LeviLamina),
+        ("bedrock_server.exe", ServerLayout::Windows),
+        ("bedrock_server", ServerLayout::Linux),
+    ]
+    .into_iter()
+    .map(|(name, layout)| (layout, path.join(name)))
+    .find(|(layout, binary)| binary.is_file() && (*layout != ServerLayout::Linux || is_elf(binary)))
+}
+
+/// Find the bedrock server in `path`, either the bds folder itself or the folder Endstone runs it
+/// from.
+pub fn detect_server<P: AsRef<Path>>(path: P) -> Option<Server> {
+    let path = path.as_ref();
+    let endstone = path.join(ENDSTONE_SERVER_DIR);
+    if endstone.is_dir() {
+        if let Some(mut server) = detect_server(&endstone) {
+            server.layout = ServerLayout::Endstone;
+            return Some(server);
         }
     }
-    Ok(false)
+    let (layout, binary) = match find_binary(path) {
+        Some((layout, binary)) => (layout, Some(binary)),
+        None if path.join("worlds").exists()
+            && path.join("bedrock_server_how_to.html").exists() =>
+        {
+            (ServerLayout::Unknown, None)
+        }
+        None => return None,
+    };
+    let layout = if path.join("endstone.toml").exists() {
+        ServerLayout::Endstone
+    } else if path.join("plugins").join("LeviLamina").exists() {
+        ServerLayout::LeviLamina
+    } else {
+        layout
+    };
+    Some(Server {
+        layout,
+        root: path.to_path_buf(),
+        binary,
+    })
+}
+
+pub fn is_bds_exist<P: AsRef<Path>>(path: P) -> Result<bool> {
+    path.as_ref()
+        .read_dir()
+        .with_context(|| "Unable to read working directory")?;
+    Ok(find_binary(path.as_ref()).is_some())
 }
 
 pub fn check_bds_exist<P: AsRef<Path>>(path: P) -> Result<()> {
@@ -39,11 +126,69 @@ pub fn is_bedrock_level<P: AsRef<Path>>(path: P) -> bool {
 }
 #[inline]
 pub fn is_bds_root<P: AsRef<Path>>(path: P) -> bool {
-    if let Ok(true) = is_bds_exist(&path) {
-        return true;
+    detect_server(path).is_some()
+}
+
+pub fn get_worlds<P: AsRef<Path>>(bds_root: P) -> Result<Vec<PathBuf>> {
+    let mut worlds = bds_root
+        .as_ref()
+        .join("worlds")
+        .read_dir()
+        .with_context(|| "Unable to read the worlds directory")?
+        .flatten()
+        .map(|i| i.path())
+        .filter(|i| is_bedrock_level(i))
+        .collect::<Vec<_>>();
+    worlds.sort();
+    Ok(worlds)
+}
+
+/// The world a bds runs when `server.properties` does not name one.
+pub const DEFAULT_LEVEL_NAME: &str = "Bedrock level";
+
+/// The `level-name` of the `server.properties` of a bds, which is the folder of the world it runs.
+pub fn read_level_name<P: AsRef<Path>>(bds_root: P) -> Option<String> {
+    fs::read_to_string(bds_root.as_ref().join("server.properties"))
+        .ok()?
+        .lines()
+        .map(str::trim)
+        .filter(|i| !i.starts_with('#'))
+        .filter_map(|i| i.split_once('='))
+        .find(|(key, _)| key.trim() == "level-name")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|i| !i.is_empty())
+}
+
+/// The name of a world shown in the game, from its `levelname.txt`.
+pub fn read_display_name<P: AsRef<Path>>(world: P) -> Option<String> {
+    fs::read_to_string(world.as_ref().join("levelname.txt"))
+        .ok()
+        .map(|i| i.trim().to_string())
+}
+
+/// Find a world of a bds by its folder name, or else by the name in its `levelname.txt`.
+pub fn find_world<P: AsRef<Path>, S: AsRef<str>>(bds_root: P, name: S) -> Result<PathBuf> {
+    let name = name.as_ref();
+    let folder = bds_root.as_ref().join("worlds").join(name);
+    if folder.exists() {
+        return Ok(folder);
+    }
+    let worlds = get_worlds(bds_root)?
+        .into_iter()
+        .filter(|i| read_display_name(i).is_some_and(|i| i == name))
+        .collect::<Vec<_>>();
+    match worlds.as_slice() {
+        [world] => Ok(world.clone()),
+        [] => Err(anyhow!("world {} not exists", name)),
+        worlds => Err(anyhow!(
+            "several worlds are named {name}, please use the folder name: {}",
+            worlds
+                .iter()
+                .map(|i| i.file_name().unwrap_or_default().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
     }
-    path.as_ref().join("worlds").exists()
-        && path.as_ref().join("bedrock_server_how_to.html").exists()
 }
 
 #[derive(Debug, PartialEq)]
@@ -1,7 +1,16 @@
-use addon_manager::*;
-use anyhow::{anyhow, Result};
+use addon_manager::{
+    addon::{
+        lang,
+        manifest::version::{AddonVersionReq, Bump},
+    },
+    *,
+};
+use anyhow::{anyhow, Context, Result};
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
-use commands::{install, list, remove};
+use commands::{
+    bump, deps, fork, history, info, install, lint, list, new, prune, remove, show, stats, trash,
+};
+use regex::Regex;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -10,11 +19,17 @@ use std::path::PathBuf;
 struct Cli {
     /// The addon to be installed
     file: Option<PathBuf>,
-    #[arg(short, long, default_value_t = String::from("Bedrock level") )]
-    /// Declare the name of the world you want to operate on. Invalid when the work path is a level.
-    world: String,
+    #[arg(short, long)]
+    /// Declare the folder or level name of the world you want to operate on. Default is the level-name of server.properties. Invalid when the work path is a level.
+    world: Option<String>,
     #[arg(long)]
     force_dirtype: Option<DirTypeFlag>,
+    #[arg(long, global = true, default_value_t = String::from(lang::DEFAULT_LANG))]
+    /// The language used to show the names and descriptions of addons that are translated in their .lang files.
+    lang: String,
+    #[arg(long, global = true)]
+    /// Never ask questions, fail instead when a choice is needed.
+    no_input: bool,
     // #[arg(long)]
     // force:bool,
     #[command(subcommand)]
@@ -31,6 +46,45 @@ enum Commands {
         /// show behavior packages
         #[arg(short, long)]
         behavior: bool,
+        /// show every package in its own table, including the description
+        #[arg(short, long, conflicts_with_all = ["columns", "wide"])]
+        long: bool,
+        /// also show installed packages that are not in use
+        #[arg(short, long)]
+        disabled: bool,
+        /// show all columns and do not truncate long values
+        #[arg(long)]
+        wide: bool,
+        /// columns to show, separated by commas
+        #[arg(long, value_delimiter = ',')]
+        columns: Vec<list::Column>,
+        /// show a matrix of the packages in use in every world of the bds
+        #[arg(long, conflicts_with = "long")]
+        all_worlds: bool,
+        /// sort the packages by the given key
+        #[arg(long, value_enum, default_value_t = list::SortKey::Priority)]
+        sort: list::SortKey,
+        /// only show packages whose name or description contains the text, ignoring case
+        #[arg(long)]
+        search: Option<String>,
+        /// only show packages whose name or description matches the regular expression
+        #[arg(long)]
+        regex: Option<Regex>,
+        /// only show packages with an author containing the text, ignoring case
+        #[arg(long)]
+        author: Option<String>,
+        /// only show packages containing modules of the given types, separated by commas
+        #[arg(long, value_delimiter = ',')]
+        module_type: Vec<list::ModuleFilter>,
+        /// only show packages whose uuid starts with the prefix
+        #[arg(long)]
+        uuid: Option<String>,
+        /// only show packages whose version matches the requirement, e.g. "^1.2.0", "^1.9.0-beta", or "1.2.0" and "[1,2,0]" for 1.2.0 or later
+        #[arg(long)]
+        version: Option<AddonVersionReq>,
+        /// only show packages whose manifest uses one of the format versions, separated by commas
+        #[arg(long, value_delimiter = ',')]
+        format_version: Vec<u32>,
     },
     /// install addon to the level.
     Install {
@@ -39,16 +93,138 @@ enum Commands {
     },
     /// Uninstall the addon to install to the level.
     Remove {
-        /// Name or uuid of the Addon to be uninstalled.
+        /// Name or uuid of the Addon to be uninstalled, a specific version can be selected with name@version or uuid@version.
         name: String,
         /// Uninstall both behavior and resource packages with the same name.
         #[arg(long)]
         all: bool,
+        /// Also uninstall the packages that depend on the uninstalled ones.
+        #[arg(long)]
+        cascade: bool,
+        /// Uninstall even if other packages depend on it.
+        #[arg(long, conflicts_with = "cascade")]
+        force: bool,
+        /// Delete the package permanently instead of moving it to the trash, and also remove its config files, valid_known_packs.json entries and history records.
+        #[arg(long)]
+        purge: bool,
+        /// Uninstall every installed version of the package.
+        #[arg(long)]
+        all_versions: bool,
+        /// Uninstall every installed version of the package except the latest one.
+        #[arg(long, conflicts_with = "all_versions")]
+        keep_latest: bool,
     },
-    // Show {
-    //     /// Name or uuid of the Addon to be show the infomation
-    //     name: String,
-    // }
+    /// Show the dependency tree of the in-use addon(s), and which addons require them.
+    Deps {
+        /// Name or uuid of the Addon to show. Default will show all in-use packages.
+        name: Option<String>,
+    },
+    /// Show the disk usage and file statistics of the installed addon(s).
+    #[command(visible_alias = "du")]
+    Stats {
+        /// show resource packages
+        #[arg(short, long)]
+        resource: bool,
+        /// show behavior packages
+        #[arg(short, long)]
+        behavior: bool,
+        /// show the statistics of every world of the bds
+        #[arg(long)]
+        all_worlds: bool,
+    },
+    /// Delete the package folders that are not used by any level. On the bds root, every level and the server packages are checked.
+    Prune {
+        /// Delete without asking for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Manage the packages removed from the level, which are kept in its trash.
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+    /// Show the packs the level has ever used, as recorded in its history files.
+    History {
+        /// show resource packages
+        #[arg(short, long)]
+        resource: bool,
+        /// show behavior packages
+        #[arg(short, long)]
+        behavior: bool,
+    },
+    /// Show the information of an installed addon, including its manifest format and problems.
+    Show {
+        /// Name, uuid or folder of the Addon to show.
+        name: String,
+    },
+    /// Check the manifests of a pack folder, a folder of packs or an archive, without installing them.
+    Lint {
+        /// The pack folder, manifest.json, .mcpack, .mcaddon or .zip to check. Default is the current directory.
+        path: Option<PathBuf>,
+        /// Rules to ignore, separated by commas.
+        #[arg(long, value_delimiter = ',')]
+        allow: Vec<String>,
+        /// Rules to report as warnings, separated by commas.
+        #[arg(long, value_delimiter = ',')]
+        warn: Vec<String>,
+        /// Rules to report as errors, separated by commas.
+        #[arg(long, value_delimiter = ',')]
+        deny: Vec<String>,
+        /// List the rules with their severities instead of checking.
+        #[arg(long)]
+        list_rules: bool,
+    },
+    /// Create the folders of a new pack project with fresh uuids.
+    New {
+        /// The kind of pack to create.
+        #[arg(value_enum)]
+        kind: new::PackKind,
+        /// The name of the pack, also used for its folder.
+        name: String,
+        /// The folder to create the pack in. Default is the current directory.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        /// The description of the pack.
+        #[arg(long, default_value_t = String::new())]
+        description: String,
+        /// An author of the pack, can be given multiple times.
+        #[arg(long)]
+        author: Vec<String>,
+        /// The version of the pack.
+        #[arg(long, default_value = "1.0.0", value_parser = new::parse_version_array)]
+        pack_version: [u64; 3],
+        /// The oldest game version the pack is written for.
+        #[arg(long, default_value = "1.21.50", value_parser = new::parse_version_array)]
+        min_engine_version: [u64; 3],
+        /// The version of @minecraft/server used by script packs.
+        #[arg(long, default_value = "1.16.0")]
+        server_version: semver::Version,
+    },
+    /// Raise the version of a pack project, along with its module versions and the dependencies between the packs.
+    Bump {
+        /// major, minor, patch, or the version to set, e.g. 1.2.0 or [1,2,0].
+        version: Bump,
+        /// The pack folders, or folders of packs such as a linked behavior and resource pack. Default is the current directory.
+        paths: Vec<PathBuf>,
+        /// Only show the changes without writing them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Copy a pack, or packs shipped together, with new uuids so the copy can be used next to the original.
+    Fork {
+        /// The pack folders, or folders of packs such as a linked behavior and resource pack.
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// A new name for the forked packs.
+        #[arg(long)]
+        name: Option<String>,
+        /// The folder to create the forked packs in. Default is the current directory.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Show the detected work directory type, server layout and worlds.
+    #[command(visible_alias = "status")]
+    Info,
     // Enable {
     //     /// Name or uuid of the Addon to enable.
     //     name: String,
@@ -65,6 +241,23 @@ enum Commands {
     // },
 }
 
+#[derive(Subcommand)]
+enum TrashCommands {
+    /// list the removed packages in the trash.
+    List,
+    /// Move a removed package back to the level.
+    Restore {
+        /// Id of the trash entry, as shown by `trash list`.
+        id: u64,
+    },
+    /// Permanently delete the packages in the trash.
+    Empty {
+        /// Only delete the packages removed longer ago than this, e.g. 30m, 12h, 7d or 2w.
+        #[arg(long, value_parser = trash::parse_age)]
+        older_than: Option<std::time::Duration>,
+    },
+}
+
 #[derive(ValueEnum, Clone)]
 enum DirTypeFlag {
     /// Skip checking and treat the working path as the bds root directory
@@ -94,6 +287,72 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // lint works on packs that are not installed, it does not need a bds or a level
+    if let Some(Commands::Lint {
+        path,
+        allow,
+        warn,
+        deny,
+        list_rules,
+    }) = &args.command
+    {
+        let config = lint::config(allow, warn, deny)?;
+        if *list_rules {
+            lint::print_rules(&config);
+            return Ok(());
+        }
+        let path = match path {
+            Some(path) => path.clone(),
+            None => std::env::current_dir()?,
+        };
+        return lint::lint(path, &config);
+    }
+
+    if let Some(Commands::New {
+        kind,
+        name,
+        dir,
+        description,
+        author,
+        pack_version,
+        min_engine_version,
+        server_version,
+    }) = &args.command
+    {
+        let template = addon::scaffold::Template {
+            name: name.clone(),
+            description: description.clone(),
+            version: *pack_version,
+            min_engine_version: *min_engine_version,
+            authors: author.clone(),
+        };
+        let dir = match dir {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        return new::new(*kind, &template, server_version.clone(), dir);
+    }
+
+    if let Some(Commands::Bump {
+        version,
+        paths,
+        dry_run,
+    }) = &args.command
+    {
+        if paths.is_empty() {
+            return bump::bump(version, &[std::env::current_dir()?], *dry_run);
+        }
+        return bump::bump(version, paths, *dry_run);
+    }
+
+    if let Some(Commands::Fork { paths, name, dir }) = &args.command {
+        let dir = match dir {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        return fork::fork(paths, name.as_deref(), dir);
+    }
+
     let workdir = std::env::current_dir()?;
     let work_dir_type = if let Some(typ) = args.force_dirtype {
         typ.into()
@@ -101,24 +360,168 @@ fn main() -> Result<()> {
         get_work_path_type(&workdir)?
     };
 
-    if work_dir_type == WorkDirType::Bds && !workdir.join("worlds").join(&args.world).exists() {
-        return Err(anyhow!("world {} not exists", &args.world));
+    let server = match work_dir_type {
+        WorkDirType::Bds => detect_server(&workdir),
+        // a level inside the worlds folder of a bds
+        WorkDirType::Level => workdir
+            .parent()
+            .and_then(|i| i.parent())
+            .and_then(detect_server),
+    };
+    if let Some(Commands::Info) = &args.command {
+        return info::info(&workdir, &work_dir_type, server.as_ref());
     }
-    let world_path = match work_dir_type {
-        WorkDirType::Bds => workdir.join("worlds").join(&args.world),
-        WorkDirType::Level => workdir,
+    // the bds may be in a sub folder of the work dir, as with Endstone
+    let workdir = match (&work_dir_type, &server) {
+        (WorkDirType::Bds, Some(server)) => server.root.clone(),
+        _ => workdir,
+    };
+    let bds_root = match work_dir_type {
+        WorkDirType::Bds => Some(workdir.clone()),
+        WorkDirType::Level => server.map(|i| i.root),
+    };
+    let world_path = || -> Result<PathBuf> {
+        if work_dir_type == WorkDirType::Level {
+            return Ok(workdir.clone());
+        }
+        match (&args.world, read_level_name(&workdir)) {
+            (Some(world), _) => find_world(&workdir, world),
+            (None, Some(level_name)) => find_world(&workdir, &level_name).with_context(|| {
+                format!(
+                    "{level_name} is the level-name of server.properties, use --world to choose another world"
+                )
+            }),
+            (None, None) => find_world(&workdir, DEFAULT_LEVEL_NAME),
+        }
     };
 
     match args.command {
         None => {
             if let Some(file) = args.file {
-                install::install(file, world_path)?;
+                install::install(file, world_path()?)?;
             }
             // args.file.is_none() && args.command.is_none() (only use command it self) is at start of this function
         }
-        Some(Commands::List { resource, behavior }) => list::list(world_path, resource, behavior)?,
-        Some(Commands::Install { file }) => install::install(file, world_path)?,
-        Some(Commands::Remove { name, all }) => remove::remove(name, all, world_path)?,
+        Some(Commands::List {
+            resource,
+            behavior,
+            long,
+            disabled,
+            wide,
+            columns,
+            all_worlds,
+            sort,
+            search,
+            regex,
+            author,
+            module_type,
+            uuid,
+            version,
+            format_version,
+        }) => {
+            let options = list::ListOptions {
+                resource,
+                behavior,
+                long,
+                wide,
+                columns,
+                sort,
+                disabled,
+                lang: args.lang,
+                filter: list::PackFilter {
+                    search,
+                    regex,
+                    author,
+                    module_types: module_type,
+                    uuid_prefix: uuid,
+                    version,
+                    format_versions: format_version,
+                },
+            };
+            if all_worlds {
+                if work_dir_type != WorkDirType::Bds {
+                    return Err(anyhow!("--all-worlds is only available on the bds root"));
+                }
+                list::list_all_worlds(&workdir, options)?
+            } else {
+                list::list(world_path()?, options)?
+            }
+        }
+        Some(Commands::Install { file }) => install::install(file, world_path()?)?,
+        Some(Commands::Remove {
+            name,
+            all,
+            cascade,
+            force,
+            purge,
+            all_versions,
+            keep_latest,
+        }) => remove::remove(
+            name,
+            world_path()?,
+            bds_root.as_deref(),
+            remove::RemoveOptions {
+                all,
+                cascade,
+                force,
+                purge,
+                all_versions,
+                keep_latest,
+                interactive: !args.no_input && commands::prompt::is_interactive(),
+                lang: args.lang,
+            },
+        )?,
+        Some(Commands::Show { name }) => show::show(name, world_path()?, &args.lang)?,
+        Some(Commands::Deps { name }) => deps::deps(name, world_path()?, &args.lang)?,
+        Some(Commands::Stats {
+            resource,
+            behavior,
+            all_worlds,
+        }) => {
+            if all_worlds {
+                if work_dir_type != WorkDirType::Bds {
+                    return Err(anyhow!("--all-worlds is only available on the bds root"));
+                }
+                stats::stats_all_worlds(&get_worlds(&workdir)?, resource, behavior, &args.lang)?
+            } else {
+                stats::stats(world_path()?, resource, behavior, &args.lang)?;
+            }
+        }
+        Some(Commands::Prune { yes }) => {
+            let interactive = !args.no_input && commands::prompt::is_interactive();
+            match work_dir_type {
+                WorkDirType::Bds => prune::prune(
+                    &get_worlds(&workdir)?,
+                    Some(&workdir),
+                    yes,
+                    interactive,
+                    &args.lang,
+                )?,
+                WorkDirType::Level => prune::prune(
+                    std::slice::from_ref(&workdir),
+                    None,
+                    yes,
+                    interactive,
+                    &args.lang,
+                )?,
+            }
+        }
+        Some(Commands::Trash { command }) => match command {
+            TrashCommands::List => trash::list(world_path()?)?,
+            TrashCommands::Restore { id } => trash::restore(world_path()?, id)?,
+            TrashCommands::Empty { older_than } => trash::empty(world_path()?, older_than)?,
+        },
+        Some(Commands::History { resource, behavior }) => {
+            history::history(world_path()?, resource, behavior)?
+        }
+        // checked before looking for the bds or the level
+        Some(
+            Commands::Lint { .. }
+            | Commands::New { .. }
+            | Commands::Bump { .. }
+            | Commands::Fork { .. }
+            | Commands::Info,
+        ) => {}
     }
     Ok(())
 }